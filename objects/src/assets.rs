@@ -0,0 +1,271 @@
+use crate::{
+    errors::ExchangeError,
+    trades::{
+        TradingPair,
+        TradingPairs,
+    },
+};
+
+use rust_decimal::Decimal;
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use std::{
+    collections::HashMap,
+    fmt,
+    hash::{
+        Hash,
+        Hasher,
+    },
+};
+
+
+
+pub type Assets = HashMap<String, AssetInfo>;
+
+// Holdings keyed by asset id, e.g. `XXBT`, or by a variant such as `XBT.F`
+// for assets earning rewards.
+pub type Balances = HashMap<String, Decimal>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetInfo {
+    pub aclass: String,
+    pub altname: String,
+    pub decimals: u32,
+    pub display_decimals: u32,
+    #[serde(default)]
+    pub collateral_value: Option<Decimal>,
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+// Altnames that the exchange renders differently in v2 symbols.
+const V2_SYMBOLS: [(&str, &str); 2] = [
+    ("XBT", "BTC"),
+    ("XDG", "DOGE"),
+];
+
+fn v2_symbol(altname: &str) -> &str {
+    match V2_SYMBOLS.iter().find(|(alt, _)| *alt == altname) {
+        Some((_, symbol)) => symbol,
+        None => altname,
+    }
+}
+
+// Aliases are matched without case or separators, so `btc/usd`, `BTC-USD`
+// and `BTCUSD` are the same key.
+fn alias_key(alias: &str) -> String {
+    alias
+        .chars()
+        .filter(|c| !matches!(c, '/' | '-' | '_' | ' '))
+        .collect::<String>()
+        .to_uppercase()
+}
+
+/* Asset */
+// Equal by the exchange's canonical id, e.g. `XXBT`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Asset {
+    id: String,
+    altname: String,
+    symbol: String,
+}
+
+impl Asset {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    // `XBT`
+    pub fn altname(&self) -> &str {
+        &self.altname
+    }
+
+    // `BTC`, as used by v2 symbols.
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+}
+
+impl PartialEq for Asset {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Asset {}
+
+impl Hash for Asset {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl fmt::Display for Asset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.id)
+    }
+}
+
+/* Pair */
+// How a pair is written for a given API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PairFormat {
+    // The canonical id, e.g. `XXBTZUSD`; the key of REST results.
+    Id,
+    // `XBTUSD`, for REST `pair` parameters.
+    Rest,
+    // `XBT/USD`, for the v1 WebSocket API.
+    WebSocket,
+    // `BTC/USD`, for the v2 WebSocket API and newer REST endpoints.
+    Symbol,
+}
+
+// Equal by the exchange's canonical id, e.g. `XXBTZUSD`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pair {
+    id: String,
+    altname: String,
+    wsname: String,
+    symbol: String,
+    base: Asset,
+    quote: Asset,
+}
+
+impl Pair {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn base(&self) -> &Asset {
+        &self.base
+    }
+
+    pub fn quote(&self) -> &Asset {
+        &self.quote
+    }
+
+    pub fn format(&self, format: PairFormat) -> &str {
+        match format {
+            PairFormat::Id => &self.id,
+            PairFormat::Rest => &self.altname,
+            PairFormat::WebSocket => &self.wsname,
+            PairFormat::Symbol => &self.symbol,
+        }
+    }
+}
+
+impl PartialEq for Pair {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Pair {}
+
+impl Hash for Pair {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl fmt::Display for Pair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.id)
+    }
+}
+
+/* Asset Catalog */
+// Resolves any name the exchange uses for an asset or pair to one `Asset` or
+// `Pair`: the canonical id (`XXBT`, `XXBTZUSD`), the altname (`XBT`,
+// `XBTUSD`), the wsname (`XBT/USD`) and the v2 symbol (`BTC`, `BTC/USD`).
+#[derive(Debug, Clone, Default)]
+pub struct AssetCatalog {
+    assets: HashMap<String, Asset>,
+    pairs: HashMap<String, Pair>,
+    pair_info: HashMap<String, TradingPair>,
+    asset_aliases: HashMap<String, String>,
+    pair_aliases: HashMap<String, String>,
+}
+
+impl AssetCatalog {
+    pub fn new(assets: &Assets, pairs: &TradingPairs) -> Self {
+        let mut catalog = Self::default();
+        for (id, info) in assets {
+            let asset = Asset {
+                id: id.clone(),
+                altname: info.altname.clone(),
+                symbol: v2_symbol(&info.altname).to_string(),
+            };
+            for alias in [&asset.id, &asset.altname, &asset.symbol] {
+                catalog.asset_aliases.insert(alias_key(alias), id.clone());
+            }
+            catalog.assets.insert(id.clone(), asset);
+        }
+        for (id, info) in pairs {
+            let (base, quote) = match (catalog.assets.get(&info.base), catalog.assets.get(&info.quote)) {
+                (Some(base), Some(quote)) => (base.clone(), quote.clone()),
+                _ => {
+                    log::debug!("Skipping pair {} with unknown assets {}/{}.", id, info.base, info.quote);
+                    continue;
+                },
+            };
+            let pair = Pair {
+                id: id.clone(),
+                altname: info.altname.clone(),
+                wsname: info.wsname.clone(),
+                symbol: format!("{}/{}", base.symbol, quote.symbol),
+                base,
+                quote,
+            };
+            for alias in [&pair.id, &pair.altname, &pair.wsname, &pair.symbol] {
+                catalog.pair_aliases.insert(alias_key(alias), id.clone());
+            }
+            catalog.pairs.insert(id.clone(), pair);
+            catalog.pair_info.insert(id.clone(), info.clone());
+        }
+        catalog
+    }
+
+    pub fn asset(&self, name: &str) -> Result<Asset, ExchangeError> {
+        self.asset_aliases
+            .get(&alias_key(name))
+            .and_then(|id| self.assets.get(id))
+            .cloned()
+            .ok_or_else(|| ExchangeError::Symbol(format!("Unknown asset {}.", name)))
+    }
+
+    pub fn pair(&self, name: &str) -> Result<Pair, ExchangeError> {
+        self.pair_aliases
+            .get(&alias_key(name))
+            .and_then(|id| self.pairs.get(id))
+            .cloned()
+            .ok_or_else(|| ExchangeError::Symbol(format!("Unknown pair {}.", name)))
+    }
+
+    // The exchange's own details of a pair, e.g. its tick size and minimums.
+    pub fn pair_info(&self, name: &str) -> Result<&TradingPair, ExchangeError> {
+        self.pair_aliases
+            .get(&alias_key(name))
+            .and_then(|id| self.pair_info.get(id))
+            .ok_or_else(|| ExchangeError::Symbol(format!("Unknown pair {}.", name)))
+    }
+
+    // Every pair with known assets, in no particular order.
+    pub fn pairs(&self) -> impl Iterator<Item = &Pair> {
+        self.pairs.values()
+    }
+
+    // The pair trading `base` against `quote`, each given by any alias.
+    pub fn pair_of(&self, base: &str, quote: &str) -> Result<Pair, ExchangeError> {
+        let (base, quote) = (self.asset(base)?, self.asset(quote)?);
+        self.pairs
+            .values()
+            .find(|pair| pair.base == base && pair.quote == quote)
+            .cloned()
+            .ok_or_else(|| ExchangeError::Symbol(format!("No pair trades {} against {}.", base.altname, quote.altname)))
+    }
+}
@@ -0,0 +1,205 @@
+use crate::{
+    assets::{
+        AssetCatalog,
+        Assets,
+        Balances,
+    },
+    clock::ClockSample,
+    encoding::Payload,
+    errors::ExchangeError,
+    exchanges::{
+        BaseExchange,
+        BaseExchangeBuilder,
+        PreparedRequest,
+    },
+    middleware::ApiRequest,
+    orders::{
+        AddOrderResult,
+        CancelOrderResult,
+        NewOrder,
+        OpenOrders,
+        OrderSummary,
+    },
+    rate_limit::Headroom,
+    system_server::{
+        ServerTime,
+        SystemStatus,
+    },
+    timestamp::Timestamp,
+    trades::{
+        AssetPairs,
+        AssetPairsInfo,
+        Tickers,
+        TradesHistory,
+    },
+    transport::TransportResponse,
+};
+
+#[cfg(feature = "config")]
+use crate::config::Profile;
+
+#[cfg(feature = "vault")]
+use crate::secrets::SecretString;
+
+#[cfg(feature = "websocket")]
+use crate::websocket::WebSocketsToken;
+
+use serde::de::DeserializeOwned;
+
+use std::sync::Arc;
+
+#[cfg(feature = "vault")]
+use std::path::Path;
+
+use tokio::runtime::{
+    Builder,
+    Runtime,
+};
+
+
+
+/* Blocking Exchange */
+// A synchronous client for code that does not run an async runtime. It
+// drives a `BaseExchange` on its own single-threaded runtime, so requests
+// are signed, rate limited, passed through middlewares and decoded exactly
+// as with the async client. Calling it from inside an async runtime panics;
+// use `BaseExchange` there instead.
+#[derive(Debug)]
+pub struct BlockingExchange {
+    exchange: BaseExchange,
+    runtime: Runtime,
+}
+
+impl BaseExchangeBuilder {
+    pub fn build_blocking(self) -> Result<BlockingExchange, ExchangeError> {
+        BlockingExchange::new(self.build()?)
+    }
+}
+
+impl BlockingExchange {
+    pub fn new(exchange: BaseExchange) -> Result<Self, ExchangeError> {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| ExchangeError::Config(format!("Error starting runtime: {}", e)))?;
+        Ok(Self {
+            exchange,
+            runtime,
+        })
+    }
+
+    #[cfg(feature = "config")]
+    pub fn from_profile(profile: &Profile) -> Result<Self, ExchangeError> {
+        Self::new(BaseExchange::from_profile(profile)?)
+    }
+
+    #[cfg(feature = "vault")]
+    pub fn from_vault(path: impl AsRef<Path>, passphrase: &SecretString, name: &str) -> Result<Self, ExchangeError> {
+        Self::new(BaseExchange::from_vault(path, passphrase, name)?)
+    }
+
+    // The async client underneath, for its settings and shared state.
+    pub fn exchange(&self) -> &BaseExchange {
+        &self.exchange
+    }
+
+    pub fn exchange_mut(&mut self) -> &mut BaseExchange {
+        &mut self.exchange
+    }
+
+    pub fn into_inner(self) -> BaseExchange {
+        self.exchange
+    }
+
+    pub fn prepare_req(&self, href: String, method: &str, data: Payload) -> Result<PreparedRequest, ExchangeError> {
+        self.runtime.block_on(self.exchange.prepare_req(href, method, data))
+    }
+
+    pub fn construct_req<T>(&self, href: String, method: &str, data: Payload) -> Result<T, ExchangeError>
+    where
+        T: DeserializeOwned,
+    {
+        self.runtime.block_on(self.exchange.construct_req(href, method, data))
+    }
+
+    pub fn execute(&self, request: ApiRequest) -> Result<TransportResponse, ExchangeError> {
+        self.runtime.block_on(self.exchange.execute(request))
+    }
+
+    pub fn get_server_time(&self) -> Result<ServerTime, ExchangeError> {
+        self.runtime.block_on(self.exchange.get_server_time())
+    }
+
+    pub fn sync_clock(&self) -> Result<ClockSample, ExchangeError> {
+        self.runtime.block_on(self.exchange.sync_clock())
+    }
+
+    pub fn get_system_status(&self) -> Result<SystemStatus, ExchangeError> {
+        self.runtime.block_on(self.exchange.get_system_status())
+    }
+
+    pub fn get_assets(&self, assets: Vec<String>) -> Result<Assets, ExchangeError> {
+        self.runtime.block_on(self.exchange.get_assets(assets))
+    }
+
+    pub fn get_tradable_asset_pairs(&self, pairs: Vec<String>, info: AssetPairsInfo) -> Result<AssetPairs, ExchangeError> {
+        self.runtime.block_on(self.exchange.get_tradable_asset_pairs(pairs, info))
+    }
+
+    pub fn get_catalog(&self) -> Result<AssetCatalog, ExchangeError> {
+        self.runtime.block_on(self.exchange.get_catalog())
+    }
+
+    pub fn catalog(&self) -> Result<Arc<AssetCatalog>, ExchangeError> {
+        self.runtime.block_on(self.exchange.catalog())
+    }
+
+    pub fn refresh_catalog(&self) -> Result<Arc<AssetCatalog>, ExchangeError> {
+        self.runtime.block_on(self.exchange.refresh_catalog())
+    }
+
+    pub fn get_ticker(&self, pairs: Vec<String>) -> Result<Tickers, ExchangeError> {
+        self.runtime.block_on(self.exchange.get_ticker(pairs))
+    }
+
+    pub fn get_balance(&self) -> Result<Balances, ExchangeError> {
+        self.runtime.block_on(self.exchange.get_balance())
+    }
+
+    pub fn get_open_orders(&self) -> Result<OpenOrders, ExchangeError> {
+        self.runtime.block_on(self.exchange.get_open_orders())
+    }
+
+    pub fn find_order(&self, cl_ord_id: &str) -> Result<Option<(String, OrderSummary)>, ExchangeError> {
+        self.runtime.block_on(self.exchange.find_order(cl_ord_id))
+    }
+
+    pub fn find_order_by_userref(&self, userref: i32, since: Timestamp) -> Result<Option<(String, OrderSummary)>, ExchangeError> {
+        self.runtime.block_on(self.exchange.find_order_by_userref(userref, since))
+    }
+
+    pub fn add_order(&self, order: NewOrder) -> Result<AddOrderResult, ExchangeError> {
+        self.runtime.block_on(self.exchange.add_order(order))
+    }
+
+    pub fn cancel_order(&self, txid: &str) -> Result<CancelOrderResult, ExchangeError> {
+        self.runtime.block_on(self.exchange.cancel_order(txid))
+    }
+
+    pub fn order_rate_headroom(&self, pair: &str) -> Option<Headroom> {
+        self.runtime.block_on(self.exchange.order_rate_headroom(pair))
+    }
+
+    pub fn get_trades_history(&self, start: Option<Timestamp>) -> Result<TradesHistory, ExchangeError> {
+        self.runtime.block_on(self.exchange.get_trades_history(start))
+    }
+
+    pub fn get_trades_history_page(&self, start: Option<Timestamp>, ofs: usize) -> Result<TradesHistory, ExchangeError> {
+        self.runtime.block_on(self.exchange.get_trades_history_page(start, ofs))
+    }
+
+    #[cfg(feature = "websocket")]
+    pub fn get_websockets_token(&self) -> Result<WebSocketsToken, ExchangeError> {
+        self.runtime.block_on(self.exchange.get_websockets_token())
+    }
+}
@@ -0,0 +1,221 @@
+use crate::{
+    errors::ExchangeError,
+    middleware::ApiRequest,
+    timestamp::Timestamp,
+    transport::TransportResponse,
+};
+
+use http::StatusCode;
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use std::{
+    collections::HashMap,
+    fs,
+    future::Future,
+    path::Path,
+    sync::{
+        Arc,
+        Mutex,
+    },
+    time::{
+        Duration,
+        SystemTime,
+    },
+};
+
+use tokio::sync::Mutex as AsyncMutex;
+
+
+
+// Public endpoints whose data rarely changes, and how long `Default` keeps it.
+pub const STATIC_ENDPOINTS: [(&str, Duration); 2] = [
+    ("/public/Assets", Duration::from_secs(3600)),
+    ("/public/AssetPairs", Duration::from_secs(3600)),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    href: String,
+    stored: Timestamp,
+    body: String,
+}
+
+impl CacheEntry {
+    fn is_fresh(&self, ttl: Duration) -> bool {
+        SystemTime::now()
+            .duration_since(self.stored.system_time())
+            .is_ok_and(|age| age < ttl)
+    }
+}
+
+/* Response Cache */
+// Opt-in cache for public GET calls, set with `BaseExchange::cache`. Only
+// endpoints with a TTL are cached, keyed by base URL, endpoint and parameters,
+// so exchanges with different base URLs can share a cache, and only
+// successful responses are kept. A call that misses while another call for the
+// same key is in flight waits for that one's result instead of sending its
+// own. Clones of an exchange share the cache.
+#[derive(Debug)]
+pub struct ResponseCache {
+    ttls: HashMap<String, Duration>,
+    entries: Mutex<HashMap<String, CacheEntry>>,
+    in_flight: Mutex<HashMap<String, Arc<AsyncMutex<()>>>>,
+}
+
+impl Default for ResponseCache {
+    fn default() -> Self {
+        STATIC_ENDPOINTS
+            .iter()
+            .fold(Self::new(), |cache, (href, ttl)| cache.with_ttl(href, *ttl))
+    }
+}
+
+impl ResponseCache {
+    // Caches nothing until endpoints are given a TTL.
+    pub fn new() -> Self {
+        Self {
+            ttls: HashMap::new(),
+            entries: Mutex::new(HashMap::new()),
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // `href` as passed to `construct_req`, e.g. `/public/AssetPairs`.
+    pub fn with_ttl(mut self, href: &str, ttl: Duration) -> Self {
+        self.ttls.insert(href.to_string(), ttl);
+        self
+    }
+
+    pub fn ttl(&self, href: &str) -> Option<Duration> {
+        self.ttls.get(href).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().expect("ResponseCache lock poisoned.").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Keys with calls under way.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.lock().expect("ResponseCache lock poisoned.").len()
+    }
+
+    // Drops every cached response for `href`, whatever its parameters.
+    pub fn invalidate(&self, href: &str) {
+        self.entries.lock().expect("ResponseCache lock poisoned.").retain(|_, entry| entry.href != href);
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().expect("ResponseCache lock poisoned.").clear();
+    }
+
+    // Answers from the cache, or awaits `fetch` and keeps its response.
+    // Requests that are not cacheable go straight to `fetch`.
+    pub async fn get_or_fetch<F>(&self, base_url: &str, request: &ApiRequest, fetch: F) -> Result<TransportResponse, ExchangeError>
+    where
+        F: Future<Output = Result<TransportResponse, ExchangeError>>,
+    {
+        let ttl = match self.ttl(&request.href) {
+            Some(ttl) if request.method == "GET" && !request.is_private() => ttl,
+            _ => return fetch.await,
+        };
+        let key = format!("{}{}?{}", base_url, request.href, request.payload.encode());
+        if let Some(resp) = self.fresh(&key, ttl) {
+            return Ok(resp);
+        }
+
+        let flight = InFlight::join(self, &key);
+        let _flight = flight.lock.lock().await;
+        // The call this one waited for may have filled the entry.
+        if let Some(resp) = self.fresh(&key, ttl) {
+            return Ok(resp);
+        }
+        let resp = fetch.await?;
+        let entry = CacheEntry {
+            href: request.href.clone(),
+            stored: Timestamp::from(SystemTime::now()),
+            body: String::from_utf8_lossy(&resp.body).into_owned(),
+        };
+        self.entries.lock().expect("ResponseCache lock poisoned.").insert(key.clone(), entry);
+        Ok(resp)
+    }
+
+    fn fresh(&self, key: &str, ttl: Duration) -> Option<TransportResponse> {
+        let entries = self.entries.lock().expect("ResponseCache lock poisoned.");
+        match entries.get(key) {
+            Some(entry) if entry.is_fresh(ttl) => Some(TransportResponse::new(StatusCode::OK, entry.body.clone())),
+            _ => None,
+        }
+    }
+
+    /* Snapshots */
+    // Writes every entry, with the time it was stored, to a JSON file.
+    pub fn save_snapshot(&self, path: impl AsRef<Path>) -> Result<(), ExchangeError> {
+        let path = path.as_ref();
+        let cache_err = |e: std::io::Error| ExchangeError::Cache(format!("{}: {}", path.display(), e));
+        let contents = {
+            let entries = self.entries.lock().expect("ResponseCache lock poisoned.");
+            serde_json::to_string(&*entries).map_err(|e| ExchangeError::Cache(e.to_string()))?
+        };
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, contents).map_err(cache_err)?;
+        fs::rename(&tmp_path, path).map_err(cache_err)
+    }
+
+    // Warms the cache from a snapshot. Entries keep their original age, so
+    // ones past their endpoint's TTL, or for endpoints without one, are
+    // skipped. Returns how many entries were loaded.
+    pub fn load_snapshot(&self, path: impl AsRef<Path>) -> Result<usize, ExchangeError> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path).map_err(|e| ExchangeError::Cache(format!("{}: {}", path.display(), e)))?;
+        let snapshot: HashMap<String, CacheEntry> = serde_json::from_str(&contents).map_err(|e| ExchangeError::Cache(e.to_string()))?;
+
+        let mut entries = self.entries.lock().expect("ResponseCache lock poisoned.");
+        let mut loaded = 0;
+        for (key, entry) in snapshot {
+            if self.ttl(&entry.href).is_some_and(|ttl| entry.is_fresh(ttl)) {
+                entries.insert(key, entry);
+                loaded += 1;
+            }
+        }
+        Ok(loaded)
+    }
+}
+
+// A call's share of the lock that makes calls for one key wait for each
+// other. Dropping the last share removes the lock, so `in_flight` only holds
+// keys with calls under way.
+struct InFlight<'a> {
+    cache: &'a ResponseCache,
+    key: &'a str,
+    lock: Arc<AsyncMutex<()>>,
+}
+
+impl<'a> InFlight<'a> {
+    fn join(cache: &'a ResponseCache, key: &'a str) -> Self {
+        let lock = cache.in_flight.lock().expect("ResponseCache lock poisoned.").entry(key.to_string()).or_default().clone();
+        Self {
+            cache,
+            key,
+            lock,
+        }
+    }
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        // Shares are only taken with the map locked, so the count cannot rise
+        // while it is checked.
+        let mut in_flight = self.cache.in_flight.lock().expect("ResponseCache lock poisoned.");
+        if Arc::strong_count(&self.lock) == 2 {
+            in_flight.remove(self.key);
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use std::{
+    sync::{
+        atomic::{
+            AtomicI64,
+            Ordering,
+        },
+        Arc,
+        Mutex,
+    },
+    time::{
+        Duration,
+        Instant,
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+
+
+
+/* Clock Offset */
+// Server time minus local time in milliseconds, shared between the exchange
+// and anything that stamps requests with a time (nonces, order timestamps).
+#[derive(Debug, Clone, Default)]
+pub struct ClockOffset(Arc<AtomicI64>);
+
+impl ClockOffset {
+    pub fn millis(&self) -> i64 {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    pub fn set_millis(&self, offset: i64) {
+        self.0.store(offset, Ordering::SeqCst);
+    }
+
+    // Converts a local clock reading to the server's clock.
+    pub fn to_server(&self, local: SystemTime) -> SystemTime {
+        let offset = self.millis();
+        match offset >= 0 {
+            true => local + Duration::from_millis(offset as u64),
+            false => local - Duration::from_millis(offset.unsigned_abs()),
+        }
+    }
+
+    pub fn server_now(&self) -> SystemTime {
+        self.to_server(SystemTime::now())
+    }
+}
+
+/* Clock Sample */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockSample {
+    pub offset_ms: i64,
+    pub round_trip: Duration,
+    pub skew_exceeded: bool,
+}
+
+/* Clock Sync */
+// Clones share the offset and the last sync, so every clone of an exchange
+// sees a refresh made by any of them.
+#[derive(Debug, Clone)]
+pub struct ClockSync {
+    pub offset: ClockOffset,
+    last_sync: Arc<Mutex<Option<LastSync>>>,
+    // Private requests refresh the offset once it is older than this. `None`
+    // only syncs when `BaseExchange::sync_clock` is called.
+    pub refresh_interval: Option<Duration>,
+    // Offsets larger than this are logged as a warning.
+    pub skew_threshold: Duration,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct LastSync {
+    round_trip: Duration,
+    at: Instant,
+}
+
+impl Default for ClockSync {
+    fn default() -> Self {
+        Self {
+            offset: ClockOffset::default(),
+            last_sync: Arc::new(Mutex::new(None)),
+            refresh_interval: None,
+            skew_threshold: Duration::from_secs(2),
+        }
+    }
+}
+
+impl ClockSync {
+    fn last_sync(&self) -> Option<LastSync> {
+        *self.last_sync.lock().expect("ClockSync lock poisoned.")
+    }
+
+    pub fn round_trip(&self) -> Option<Duration> {
+        self.last_sync().map(|last| last.round_trip)
+    }
+
+    pub fn synced_at(&self) -> Option<Instant> {
+        self.last_sync().map(|last| last.at)
+    }
+
+    pub fn needs_refresh(&self) -> bool {
+        match (self.refresh_interval, self.synced_at()) {
+            (None, _) => false,
+            (Some(_), None) => true,
+            (Some(interval), Some(synced_at)) => synced_at.elapsed() >= interval,
+        }
+    }
+
+    // Records a `/public/Time` reading taken between `sent` and `received`.
+    // The server is assumed to have answered halfway through the round trip.
+    // It reports the time truncated to whole seconds, so the middle of that
+    // second is taken as its reading and the offset is accurate to about 500ms.
+    pub fn record(&self, server_unixtime: i64, sent: SystemTime, received: SystemTime) -> ClockSample {
+        let round_trip = received.duration_since(sent).unwrap_or_default();
+        let midpoint = sent + round_trip / 2;
+        let local_ms = midpoint.duration_since(UNIX_EPOCH).expect("Error creating since_epoch").as_millis() as i64;
+        let offset_ms = server_unixtime * 1000 + 500 - local_ms;
+
+        self.offset.set_millis(offset_ms);
+        *self.last_sync.lock().expect("ClockSync lock poisoned.") = Some(LastSync {
+            round_trip,
+            at: Instant::now(),
+        });
+
+        let skew_exceeded = offset_ms.unsigned_abs() > self.skew_threshold.as_millis() as u64;
+        if skew_exceeded {
+            log::warn!(
+                "Local clock differs from the exchange by {}ms (threshold {:?}, round trip {:?}).",
+                offset_ms,
+                self.skew_threshold,
+                round_trip,
+            );
+        }
+        ClockSample {
+            offset_ms,
+            round_trip,
+            skew_exceeded,
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use crate::{
+    errors::ExchangeError,
+    rate_limit::{
+        AccountTier,
+        RateLimitMode,
+    },
+    secrets::SecretString,
+};
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use std::{
+    collections::HashMap,
+    fs::read_to_string,
+    path::Path,
+};
+
+
+
+/* OTP Method */
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "lowercase")]
+pub enum OtpConfig {
+    #[default]
+    None,
+    Static {
+        password: SecretString,
+    },
+    Totp {
+        secret: SecretString,
+    },
+}
+
+/* Timeouts */
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Timeouts {
+    // Seconds allowed to establish a connection.
+    pub connect: Option<u64>,
+    // Seconds allowed for the whole request.
+    pub request: Option<u64>,
+}
+
+/* Profile */
+// One account: a key pair and how to reach and authenticate against the exchange.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    #[serde(default)]
+    pub base_url: String,
+    #[serde(default)]
+    pub api_key: String,
+    // Base64, as issued by the exchange.
+    #[serde(default)]
+    pub api_secret: SecretString,
+    #[serde(default)]
+    pub otp: OtpConfig,
+    #[serde(default)]
+    pub timeouts: Timeouts,
+    #[serde(default)]
+    pub rate_tier: Option<AccountTier>,
+    #[serde(default)]
+    pub rate_limit_mode: Option<RateLimitMode>,
+    // Unix socket of a signing daemon that holds the secret instead of this
+    // process; `api_secret` may then be left empty.
+    #[serde(default)]
+    pub signer_socket: Option<String>,
+    // Reported by `unified::Exchange::venue`; the base URL's host if unset.
+    #[serde(default)]
+    pub venue: Option<String>,
+    // WebSocket endpoints, used with the `websocket` feature.
+    #[serde(default)]
+    pub ws_public_url: Option<String>,
+    #[serde(default)]
+    pub ws_private_url: Option<String>,
+}
+
+/* Config */
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Config {
+    #[serde(default)]
+    pub default_profile: Option<String>,
+    #[serde(default)]
+    pub profiles: HashMap<String, Profile>,
+}
+
+// Prefix of the environment variables that override profile values, e.g.
+// `EXCHANGE_MAIN_API_KEY` sets `api_key` on the `main` profile.
+pub const ENV_PREFIX: &str = "EXCHANGE_";
+
+const ENV_FIELDS: [&str; 13] = [
+    "BASE_URL",
+    "API_KEY",
+    "API_SECRET",
+    "OTP_PASSWORD",
+    "TOTP_SECRET",
+    "CONNECT_TIMEOUT",
+    "REQUEST_TIMEOUT",
+    "RATE_TIER",
+    "RATE_LIMIT_MODE",
+    "SIGNER_SOCKET",
+    "VENUE",
+    "WS_PUBLIC_URL",
+    "WS_PRIVATE_URL",
+];
+
+impl Config {
+    // Reads a `.toml`, `.yaml` or `.yml` file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ExchangeError> {
+        let path = path.as_ref();
+        let contents = read_to_string(path).map_err(|e| ExchangeError::Config(format!("{}: {}", path.display(), e)))?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml_str(&contents),
+            Some("yaml") | Some("yml") => Self::from_yaml_str(&contents),
+            _ => Err(ExchangeError::Config(format!("{}: expected a .toml, .yaml or .yml file", path.display()))),
+        }
+    }
+
+    pub fn from_toml_str(contents: &str) -> Result<Self, ExchangeError> {
+        toml::from_str(contents).map_err(|e| ExchangeError::Config(e.to_string()))
+    }
+
+    pub fn from_yaml_str(contents: &str) -> Result<Self, ExchangeError> {
+        serde_yaml::from_str(contents).map_err(|e| ExchangeError::Config(e.to_string()))
+    }
+
+    // Applies `EXCHANGE_<PROFILE>_<FIELD>` overrides, creating profiles that
+    // only exist in the environment. Takes the variables explicitly so callers
+    // decide where they come from, e.g. `config.apply_env(std::env::vars())`.
+    pub fn apply_env<I>(&mut self, vars: I) -> Result<(), ExchangeError>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        for (key, value) in vars {
+            let rest = match key.strip_prefix(ENV_PREFIX) {
+                Some(rest) => rest,
+                None => continue,
+            };
+            if rest == "DEFAULT_PROFILE" {
+                self.default_profile = Some(value.to_lowercase());
+                continue;
+            }
+            let (name, field) = match ENV_FIELDS
+                .iter()
+                .find_map(|field| rest.strip_suffix(field).and_then(|n| n.strip_suffix('_')).map(|n| (n, *field)))
+            {
+                Some(found) => found,
+                None => continue,
+            };
+            let profile = self.profiles.entry(name.to_lowercase()).or_default();
+            profile.set_env_field(field, value)?;
+        }
+        Ok(())
+    }
+
+    // The named profile, or the default one when `name` is `None`.
+    pub fn profile(&self, name: Option<&str>) -> Result<&Profile, ExchangeError> {
+        let name = match name.or(self.default_profile.as_deref()) {
+            Some(name) => name,
+            None => return Err(ExchangeError::Config("No profile selected and no default_profile set.".to_string())),
+        };
+        match self.profiles.get(name) {
+            Some(profile) => Ok(profile),
+            None => Err(ExchangeError::Config(format!("Unknown profile {}.", name))),
+        }
+    }
+}
+
+impl Profile {
+    fn set_env_field(&mut self, field: &str, value: String) -> Result<(), ExchangeError> {
+        let parse_secs = |value: &str| -> Result<u64, ExchangeError> {
+            value.parse().map_err(|_| ExchangeError::Config(format!("Invalid {} timeout {:?}.", field, value)))
+        };
+        match field {
+            "BASE_URL" => self.base_url = value,
+            "API_KEY" => self.api_key = value,
+            "API_SECRET" => self.api_secret = SecretString::from(value),
+            "OTP_PASSWORD" => self.otp = OtpConfig::Static { password: SecretString::from(value) },
+            "TOTP_SECRET" => self.otp = OtpConfig::Totp { secret: SecretString::from(value) },
+            "CONNECT_TIMEOUT" => self.timeouts.connect = Some(parse_secs(&value)?),
+            "REQUEST_TIMEOUT" => self.timeouts.request = Some(parse_secs(&value)?),
+            "RATE_TIER" => self.rate_tier = Some(parse_enum(field, &value)?),
+            "RATE_LIMIT_MODE" => self.rate_limit_mode = Some(parse_enum(field, &value)?),
+            "SIGNER_SOCKET" => self.signer_socket = Some(value),
+            "VENUE" => self.venue = Some(value),
+            "WS_PUBLIC_URL" => self.ws_public_url = Some(value),
+            "WS_PRIVATE_URL" => self.ws_private_url = Some(value),
+            _ => {},
+        }
+        Ok(())
+    }
+
+    pub fn requires_otp(&self) -> bool {
+        self.otp != OtpConfig::None
+    }
+}
+
+fn parse_enum<T: serde::de::DeserializeOwned>(field: &str, value: &str) -> Result<T, ExchangeError> {
+    serde_json::from_value(serde_json::Value::String(value.to_lowercase()))
+        .map_err(|_| ExchangeError::Config(format!("Invalid {} {:?}.", field, value)))
+}
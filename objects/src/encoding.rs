@@ -0,0 +1,92 @@
+use urlencoding::encode;
+
+
+
+/* Request Payload */
+// Ordered request parameters. The string produced by `encode` is both the
+// signed payload and the body (or query string) that goes on the wire.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Payload {
+    params: Vec<(String, String)>,
+}
+
+impl Payload {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Sets `key`, replacing the value in place if it already exists.
+    pub fn insert(&mut self, key: &str, value: impl ToString) -> &mut Self {
+        let value = value.to_string();
+        match self.params.iter_mut().find(|(k, _)| k == key) {
+            Some(param) => param.1 = value,
+            None => self.params.push((key.to_string(), value)),
+        }
+        self
+    }
+
+    // Appends `key` without replacing earlier values.
+    pub fn push(&mut self, key: &str, value: impl ToString) -> &mut Self {
+        self.params.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    // Appends one `key[]=value` pair per value, e.g. `txid[]`.
+    pub fn insert_array<I, V>(&mut self, key: &str, values: I) -> &mut Self
+    where
+        I: IntoIterator<Item = V>,
+        V: ToString,
+    {
+        let array_key = format!("{}[]", key.trim_end_matches("[]"));
+        self.params.retain(|(k, _)| k != &array_key);
+        for value in values {
+            self.params.push((array_key.clone(), value.to_string()));
+        }
+        self
+    }
+
+    // Sets `key` as the first parameter, used for the nonce.
+    pub fn insert_first(&mut self, key: &str, value: impl ToString) -> &mut Self {
+        self.params.retain(|(k, _)| k != key);
+        self.params.insert(0, (key.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.params.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.params.iter().any(|(k, _)| k == key)
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        let position = self.params.iter().position(|(k, _)| k == key)?;
+        Some(self.params.remove(position).1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.params.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    // application/x-www-form-urlencoded, parameters kept in insertion order.
+    pub fn encode(&self) -> String {
+        self.params
+            .iter()
+            .map(|(key, value)| format!("{}={}", encode(key), encode(value)))
+            .collect::<Vec<String>>()
+            .join("&")
+    }
+}
+
+impl<K: ToString, V: ToString> FromIterator<(K, V)> for Payload {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self {
+            params: iter.into_iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use http::StatusCode;
+
+use std::{
+    fmt,
+    time::Duration,
+};
+
+
+
+/* Error Enum */
+#[derive(Debug)]
+pub enum ExchangeError {
+    // Non-200 HTTP status.
+    Status(StatusCode),
+    // The request could not be sent or the response could not be read.
+    Request(String),
+    // The response body did not match the expected result type.
+    Decode(String),
+    // The exchange returned a non-empty `error` array.
+    Api(Vec<String>),
+    // The response had no errors and no `result`.
+    MissingResult,
+    // The call would exceed a rate limit; retry after the given wait.
+    RateLimited(Duration),
+    // A nonce could not be issued.
+    Nonce(String),
+    // A one-time password could not be generated.
+    Otp(String),
+    // A secret could not be loaded.
+    Secret(String),
+    // Configuration could not be read or is incomplete.
+    Config(String),
+    // The credential vault could not be read or written.
+    Vault(String),
+    // A request could not be signed, or the signer refused to sign it.
+    Signing(String),
+    // An asset or pair name is not in the catalog.
+    Symbol(String),
+    // An order does not meet the pair's trading rules.
+    InvalidOrder(String),
+    // No order with this id was found to cancel.
+    UnknownOrder(String),
+    // A response cache snapshot could not be read or written.
+    Cache(String),
+}
+
+impl fmt::Display for ExchangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExchangeError::Status(status) => write!(f, "HTTP status {}", status),
+            ExchangeError::Request(err) => write!(f, "Request error: {}", err),
+            ExchangeError::Decode(err) => write!(f, "Error decoding response: {}", err),
+            ExchangeError::Api(errors) => write!(f, "API error: {}", errors.join(", ")),
+            ExchangeError::MissingResult => write!(f, "Response contained no result"),
+            ExchangeError::RateLimited(wait) => write!(f, "Rate limit exceeded, retry in {:?}", wait),
+            ExchangeError::Nonce(err) => write!(f, "Error creating nonce: {}", err),
+            ExchangeError::Otp(err) => write!(f, "Error creating OTP: {}", err),
+            ExchangeError::Secret(err) => write!(f, "Error loading secret: {}", err),
+            ExchangeError::Config(err) => write!(f, "Configuration error: {}", err),
+            ExchangeError::Vault(err) => write!(f, "Vault error: {}", err),
+            ExchangeError::Signing(err) => write!(f, "Error signing request: {}", err),
+            ExchangeError::Symbol(err) => write!(f, "Symbol error: {}", err),
+            ExchangeError::InvalidOrder(err) => write!(f, "Invalid order: {}", err),
+            ExchangeError::UnknownOrder(id) => write!(f, "Unknown order: {}", id),
+            ExchangeError::Cache(err) => write!(f, "Cache error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ExchangeError {}
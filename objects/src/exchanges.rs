@@ -1,274 +1,965 @@
 use crate::{
-    responses::{
-        APIResponse,
-        TradingPairResponse
+    assets::{
+        AssetCatalog,
+        Assets,
+        Balances,
+    },
+    cache::ResponseCache,
+    clock::{
+        ClockSample,
+        ClockSync,
+    },
+    encoding::Payload,
+    errors::ExchangeError,
+    middleware::{
+        ApiRequest,
+        Middleware,
+        Next,
+    },
+    nonce::{
+        IncreasingNonce,
+        NonceProvider,
+    },
+    otp::OtpProvider,
+    orders::{
+        AddOrderDescription,
+        AddOrderResult,
+        CancelOrderResult,
+        NewOrder,
+        OpenOrders,
+        OrderLookup,
+        OrderSummary,
+        Rounding,
+    },
+    rate_limit::{
+        EndpointCost,
+        Headroom,
+        RateLimitMode,
+        RateLimiter,
+    },
+    responses::APIResponse,
+    retry::{
+        is_idempotent,
+        ErrorClass,
+        RetryPolicy,
+    },
+    secrets::SecretKey,
+    signer::{
+        hmac_sign,
+        Signer,
+    },
+    system_server::{
+        ServerTime,
+        SystemStatus,
+    },
+    timestamp::Timestamp,
+    trades::{
+        AssetPairs,
+        AssetPairsInfo,
+        Tickers,
+        TradesHistory,
+        TradingPairs,
+    },
+    transport::{
+        HttpConfig,
+        Transport,
+        TransportResponse,
     },
 };
 
-use base64::{
-    decode,
-    encode,
+#[cfg(feature = "config")]
+use crate::{
+    config::{
+        OtpConfig,
+        Profile,
+    },
+    otp::StaticOtp,
 };
 
-use hmac::{
-    Hmac, 
-    Mac, 
-    NewMac
+#[cfg(all(feature = "config", feature = "otp"))]
+use crate::otp::Totp;
+
+#[cfg(feature = "vault")]
+use crate::{
+    secrets::SecretString,
+    vault::Vault,
 };
 
-use reqwest::{
+#[cfg(all(unix, feature = "config"))]
+use crate::signer::DaemonSigner;
+
+#[cfg(feature = "rest")]
+use crate::transport::ReqwestTransport;
+
+use http::{
     header::{
+        CONTENT_TYPE,
         HeaderMap,
         HeaderValue,
     },
     StatusCode,
 };
 
-use sha2::{
-    Digest,
-    Sha256,
-    Sha512,
+use serde::de::{
+    DeserializeOwned,
+    IgnoredAny,
 };
 
-use serde::de::DeserializeOwned;
-
-use serde_json::json;
-
 use std::{
     collections::HashMap,
-    env::var,
+    fmt,
+    sync::{
+        Arc,
+        Mutex,
+        RwLock,
+    },
     time::{
-        SystemTime, 
-        UNIX_EPOCH,
+        Duration,
+        SystemTime,
     },
 };
 
+use tokio::{
+    sync::Mutex as AsyncMutex,
+    task::spawn_blocking,
+    time::sleep,
+};
+
+#[cfg(feature = "vault")]
+use std::path::Path;
+
+#[cfg(feature = "order-ids")]
+use uuid::Uuid;
 
 
-#[derive(Debug)]
+
+#[derive(Clone)]
+pub struct PreparedRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: HeaderMap,
+    pub body: Option<String>,
+}
+
+// `API-Key` and `API-Sign` are sensitive headers, which `HeaderMap` masks,
+// and the OTP is masked in the body.
+impl fmt::Debug for PreparedRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PreparedRequest")
+            .field("method", &self.method)
+            .field("url", &self.url)
+            .field("headers", &self.headers)
+            .field("body", &self.body.as_deref().map(redact_otp))
+            .finish()
+    }
+}
+
+fn redact_otp(body: &str) -> String {
+    body.split('&')
+        .map(|param| match param.starts_with("otp=") {
+            true => "otp=***",
+            false => param,
+        })
+        .collect::<Vec<&str>>()
+        .join("&")
+}
+
+// Endpoint methods take `&self`, so one instance can be shared behind an
+// `Arc` across tasks. Clones share the nonce provider, rate limiter, clock
+// offset and connection pool.
+#[derive(Debug, Clone)]
 pub struct BaseExchange {
     pub api_key: String,
-    pub api_secret: String,
+    pub api_secret: SecretKey,
     pub api_key_2fa: String,
-    pub api_secret_2fa: String,
-    pub api_passphrase: String,
+    pub api_secret_2fa: SecretKey,
     pub base_url: String,
-    pub client: reqwest::Client,
+    pub transport: Arc<dyn Transport>,
     pub api_passphrase_required: Option<bool>,
+    pub rate_limiter: Option<Arc<Mutex<RateLimiter>>>,
+    pub nonce_provider: Arc<dyn NonceProvider>,
+    pub clock: ClockSync,
+    pub otp: Option<Arc<dyn OtpProvider>>,
+    // Signs private requests in place of the active API secret, e.g. a
+    // `DaemonSigner` so this process never holds the secret.
+    pub signer: Option<Arc<dyn Signer>>,
+    // Run in order around every call, outermost first.
+    pub middlewares: Vec<Arc<dyn Middleware>>,
+    // Retries idempotent calls, and `add_order` via its client order id.
+    pub retry_policy: Option<RetryPolicy>,
+    // Caches public data such as assets and pairs; off by default.
+    pub cache: Option<Arc<ResponseCache>>,
+    // Reported by `unified::Exchange::venue`; the base URL's host if unset.
+    pub venue: Option<String>,
+    // WebSocket endpoints for public and private channels, e.g.
+    // `wss://ws.example.com/v2`. Empty until set from a profile or builder.
+    #[cfg(feature = "websocket")]
+    pub ws_public_url: String,
+    #[cfg(feature = "websocket")]
+    pub ws_private_url: String,
+    // Filled by the first call to `catalog()`. Reads never wait on a fetch,
+    // which holds `catalog_fetch` instead.
+    catalog: Arc<RwLock<Option<Arc<AssetCatalog>>>>,
+    catalog_fetch: Arc<AsyncMutex<()>>,
 }
 
+#[cfg(feature = "rest")]
 impl Default for BaseExchange {
     fn default() -> Self {
-        let client = reqwest::Client::builder().build().unwrap();
+        Self::with_transport(Arc::new(ReqwestTransport::default()))
+    }
+}
+
+/* Builder */
+#[derive(Debug, Default)]
+pub struct BaseExchangeBuilder {
+    pub http: HttpConfig,
+    #[cfg(feature = "rest")]
+    client: Option<reqwest::Client>,
+    transport: Option<Arc<dyn Transport>>,
+    base_url: Option<String>,
+    venue: Option<String>,
+    #[cfg(feature = "websocket")]
+    ws_urls: Option<(String, String)>,
+    #[cfg(feature = "config")]
+    profile: Option<Profile>,
+    cache: Option<Arc<ResponseCache>>,
+}
+
+impl BaseExchangeBuilder {
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = Some(base_url.to_string());
+        self
+    }
+
+    pub fn venue(mut self, venue: &str) -> Self {
+        self.venue = Some(venue.to_string());
+        self
+    }
+
+    // Endpoints for public and private WebSocket channels.
+    #[cfg(feature = "websocket")]
+    pub fn websocket_urls(mut self, public: &str, private: &str) -> Self {
+        self.ws_urls = Some((public.to_string(), private.to_string()));
+        self
+    }
+
+    // Credentials, OTP, signer and rate tier from a configured account. Its
+    // timeouts override the ones set here.
+    #[cfg(feature = "config")]
+    pub fn profile(mut self, profile: &Profile) -> Self {
+        self.profile = Some(profile.clone());
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.http.connect_timeout = Some(timeout);
+        self
+    }
+
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.http.request_timeout = Some(timeout);
+        self
+    }
+
+    pub fn pool_max_idle_per_host(mut self, max_idle: usize) -> Self {
+        self.http.pool_max_idle_per_host = Some(max_idle);
+        self
+    }
+
+    pub fn pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.http.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    pub fn proxy(mut self, proxy: &str) -> Self {
+        self.http.proxy = Some(proxy.to_string());
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.http.user_agent = Some(user_agent.to_string());
+        self
+    }
+
+    pub fn tcp_keepalive(mut self, interval: Duration) -> Self {
+        self.http.tcp_keepalive = Some(interval);
+        self
+    }
+
+    pub fn http2_prior_knowledge(mut self) -> Self {
+        self.http.http2_prior_knowledge = true;
+        self
+    }
+
+    // Shares an existing client, and its connection pool, with other
+    // exchanges. The HTTP settings above are then ignored.
+    #[cfg(feature = "rest")]
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    // Sends through any transport instead of reqwest.
+    pub fn transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    // Pass the same cache to several builders to share it.
+    pub fn cache(mut self, cache: Arc<ResponseCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    pub fn build(mut self) -> Result<BaseExchange, ExchangeError> {
+        #[cfg(feature = "config")]
+        if let Some(profile) = &self.profile {
+            if let Some(connect) = profile.timeouts.connect {
+                self.http.connect_timeout = Some(Duration::from_secs(connect));
+            }
+            if let Some(request) = profile.timeouts.request {
+                self.http.request_timeout = Some(Duration::from_secs(request));
+            }
+        }
+        let transport: Arc<dyn Transport> = match self.transport.take() {
+            Some(transport) => transport,
+            None => self.http_transport()?,
+        };
+        let mut exchange = BaseExchange::with_transport(transport);
+        #[cfg(feature = "config")]
+        if let Some(profile) = &self.profile {
+            exchange.apply_profile(profile)?;
+        }
+        if let Some(base_url) = self.base_url {
+            exchange.base_url = base_url;
+        }
+        if let Some(venue) = self.venue {
+            exchange.venue = Some(venue);
+        }
+        #[cfg(feature = "websocket")]
+        if let Some((public, private)) = self.ws_urls {
+            exchange.ws_public_url = public;
+            exchange.ws_private_url = private;
+        }
+        exchange.cache = self.cache;
+        Ok(exchange)
+    }
+
+    #[cfg(feature = "rest")]
+    fn http_transport(&mut self) -> Result<Arc<dyn Transport>, ExchangeError> {
+        match self.client.take() {
+            Some(client) => Ok(Arc::new(ReqwestTransport::new(client))),
+            None => Ok(Arc::new(ReqwestTransport::new(self.http.build_client()?))),
+        }
+    }
+
+    #[cfg(not(feature = "rest"))]
+    fn http_transport(&mut self) -> Result<Arc<dyn Transport>, ExchangeError> {
+        Err(ExchangeError::Config("No transport set, and the rest feature that provides the HTTP one is disabled.".to_string()))
+    }
+}
+
+impl BaseExchange {
+    pub fn builder() -> BaseExchangeBuilder {
+        BaseExchangeBuilder::default()
+    }
+
+    fn with_transport(transport: Arc<dyn Transport>) -> Self {
+        let clock = ClockSync::default();
+        let nonce_provider = IncreasingNonce::default().with_clock_offset(clock.offset.clone());
         Self {
             api_key: "".to_string(),
-            api_secret: "".to_string(),
+            api_secret: SecretKey::default(),
             api_key_2fa: "".to_string(),
-            api_secret_2fa: "".to_string(),
-            api_passphrase: "".to_string(),
+            api_secret_2fa: SecretKey::default(),
             base_url: "".to_string(),
-            client: client,
+            transport,
             api_passphrase_required: None,
+            rate_limiter: None,
+            nonce_provider: Arc::new(nonce_provider),
+            clock,
+            otp: None,
+            signer: None,
+            middlewares: Vec::new(),
+            retry_policy: None,
+            cache: None,
+            venue: None,
+            #[cfg(feature = "websocket")]
+            ws_public_url: "".to_string(),
+            #[cfg(feature = "websocket")]
+            ws_private_url: "".to_string(),
+            catalog: Arc::new(RwLock::new(None)),
+            catalog_fetch: Arc::new(AsyncMutex::new(())),
         }
     }
-}
 
-impl BaseExchange {
-    pub fn api_pass_required(&mut self) -> bool {
-        if self.api_passphrase_required.is_none() {
-            self.set_api_passphrase_required();
+    // Builds an exchange for one configured account.
+    #[cfg(feature = "config")]
+    pub fn from_profile(profile: &Profile) -> Result<Self, ExchangeError> {
+        Self::builder().profile(profile).build()
+    }
+
+    // Profiles with an OTP method use the 2FA key slot and attach a code to
+    // private requests.
+    #[cfg(feature = "config")]
+    fn apply_profile(&mut self, profile: &Profile) -> Result<(), ExchangeError> {
+        if profile.base_url.is_empty() {
+            return Err(ExchangeError::Config("Profile is missing base_url.".to_string()));
+        }
+        let api_secret = match profile.api_secret.is_empty() {
+            true => SecretKey::default(),
+            false => SecretKey::from_base64(profile.api_secret.expose())?,
+        };
+        let otp: Option<Arc<dyn OtpProvider>> = match &profile.otp {
+            OtpConfig::None => None,
+            OtpConfig::Static { password } => Some(Arc::new(StaticOtp::new(password.expose()))),
+            #[cfg(feature = "otp")]
+            OtpConfig::Totp { secret } => Some(Arc::new(Totp::from_base32(secret.expose())?)),
+            #[cfg(not(feature = "otp"))]
+            OtpConfig::Totp { .. } => return Err(ExchangeError::Config("TOTP profiles require the otp feature.".to_string())),
+        };
+        self.signer = match &profile.signer_socket {
+            #[cfg(unix)]
+            Some(socket) => Some(Arc::new(DaemonSigner::new(socket))),
+            #[cfg(not(unix))]
+            Some(_) => return Err(ExchangeError::Config("signer_socket requires Unix sockets.".to_string())),
+            None => None,
+        };
+
+        self.base_url = profile.base_url.clone();
+        self.venue = profile.venue.clone();
+        #[cfg(feature = "websocket")]
+        {
+            self.ws_public_url = profile.ws_public_url.clone().unwrap_or_default();
+            self.ws_private_url = profile.ws_private_url.clone().unwrap_or_default();
         }
+        self.api_passphrase_required = Some(otp.is_some());
+        self.rate_limiter = profile.rate_tier.map(|tier| {
+            Arc::new(Mutex::new(RateLimiter::new(tier, profile.rate_limit_mode.unwrap_or(RateLimitMode::Queue))))
+        });
+        match otp.is_some() {
+            true => {
+                self.api_key_2fa = profile.api_key.clone();
+                self.api_secret_2fa = api_secret;
+            },
+            false => {
+                self.api_key = profile.api_key.clone();
+                self.api_secret = api_secret;
+            },
+        }
+        self.otp = otp;
+        Ok(())
+    }
+
+    pub fn with_middleware(mut self, middleware: Arc<dyn Middleware>) -> Self {
+        self.middlewares.push(middleware);
+        self
+    }
+
+    // Loads a named profile from an encrypted credential vault.
+    #[cfg(feature = "vault")]
+    pub fn from_vault(path: impl AsRef<Path>, passphrase: &SecretString, name: &str) -> Result<Self, ExchangeError> {
+        let vault = Vault::open(path, passphrase)?;
+        Self::from_profile(vault.profile(name)?)
+    }
+
+    // When not set on the exchange, 2FA is required if an OTP provider is.
+    pub fn api_pass_required(&self) -> bool {
         match self.api_passphrase_required {
             Some(val) => val,
-            None => panic!("Error setting api_passphrase requirement.") 
-        }
-    }
-    fn set_api_passphrase_required(&mut self) {
-        self.api_passphrase_required = Some(
-            match var("API_PASSPHRASE_REQUIRED") {
-                Ok(val) => {
-                    match val.is_empty() {
-                        true => false,
-                        false => {
-                            val == "1"
-                        },
-                    }
-                },
-                Err(_) => false
-            }
-        )
+            None => self.otp.is_some(),
+        }
     }
-    fn nonce() -> u128 {
-        let current_time = SystemTime::now();
-        let since_epoch = current_time.duration_since(UNIX_EPOCH).expect("Error creating since_epoch");
-        since_epoch.as_millis()
+
+    // A fresh one-time password for a request made now, on the server's clock.
+    pub fn otp_code(&self) -> Result<String, ExchangeError> {
+        match &self.otp {
+            Some(otp) => otp.code(self.clock.offset.server_now()),
+            None => Err(ExchangeError::Otp("Unable to issue request due to a missing OTP provider while 2FA is required.".to_string())),
+        }
     }
 
-    pub async fn construct_req<T>(&mut self, href: String, method: &str, mut data: HashMap<String, String>) -> Result<T, reqwest::StatusCode> 
-    where 
-        T: DeserializeOwned,
-    {
-        let api_passphrase_required: bool = match self.api_passphrase_required {
-            Some(val) => val,
-            None => {
-                self.set_api_passphrase_required();
-                self.api_passphrase_required.unwrap()
-            }
+    pub fn nonce(&self) -> Result<u64, ExchangeError> {
+        self.nonce_provider.next_nonce()
+    }
+
+    // Reserves room for the call on the rate limiter, if one is configured.
+    // In queue mode this waits for the counters to decay, in reject mode it
+    // fails straight away.
+    async fn wait_for_rate_limit(&self, href: &str, pair: Option<&str>) -> Result<(), ExchangeError> {
+        let limiter = match &self.rate_limiter {
+            Some(limiter) => limiter,
+            None => return Ok(()),
         };
+        loop {
+            // The lock is released before waiting.
+            let (wait, mode) = {
+                let mut limiter = limiter.lock().expect("RateLimiter lock poisoned.");
+                match limiter.reserve(href, pair) {
+                    Ok(()) => return Ok(()),
+                    Err(wait) => (wait, limiter.mode),
+                }
+            };
+            match mode {
+                RateLimitMode::Reject => return Err(ExchangeError::RateLimited(wait)),
+                RateLimitMode::Queue => sleep(wait).await,
+            }
+        }
+    }
+
+    pub fn rate_limit_headroom(&self) -> Option<Headroom> {
+        self.rate_limiter
+            .as_ref()
+            .map(|limiter| limiter.lock().expect("RateLimiter lock poisoned.").headroom())
+    }
+
+    pub async fn order_rate_headroom(&self, pair: &str) -> Option<Headroom> {
+        let limiter = self.rate_limiter.as_ref()?;
+        self.load_catalog_for_rate_limit().await;
+        let pair = self.rate_limit_pair(pair);
+        let headroom = limiter.lock().expect("RateLimiter lock poisoned.").order_headroom(&pair);
+        Some(headroom)
+    }
+
+    async fn load_catalog_for_rate_limit(&self) {
+        if let Err(e) = self.catalog().await {
+            log::warn!("Rate limiting orders by pair name, the asset catalog failed to load: {}", e);
+        }
+    }
+
+    // The key of a pair's order-rate counter: its canonical id, so that
+    // `XBT/USD`, `XBTUSD` and `XXBTZUSD` share one counter as they do on the
+    // exchange. Only a loaded catalog is used, as loading one sends requests
+    // through `dispatch`; without it the name is used as given.
+    fn rate_limit_pair(&self, name: &str) -> String {
+        match self.loaded_catalog().map(|catalog| catalog.pair(name)) {
+            Some(Ok(pair)) => pair.id().to_string(),
+            _ => name.to_string(),
+        }
+    }
+
+    // Builds the exact request that will be sent. For private endpoints the
+    // body is encoded once and that same string is signed.
+    pub async fn prepare_req(&self, href: String, method: &str, mut data: Payload) -> Result<PreparedRequest, ExchangeError> {
         let mut req_url: String = format!("{}{}",self.base_url,href);
-        let data_empty: bool = data.is_empty();
-        let r = match method {
+        let mut headers = HeaderMap::new();
+        let mut body: Option<String> = None;
+        match method {
             "GET" => {
-                if !data_empty {
-                    req_url.push_str("?");
-                    let mut params: Vec<String> = Vec::new();
-                    for (key, val) in data.clone().into_iter() {
-                        params.push(format!("{}={}",key,val));
-                    }
-                    req_url.push_str(&params.join("&"));
+                if !data.is_empty() {
+                    req_url.push('?');
+                    req_url.push_str(&data.encode());
                 }
-                self.client.get(req_url)
             },
-            "POST" => self.client.post(req_url),
-            _ => panic!("Error invalid method"),
-        };
-        let resp = match href.contains("private") {
-            true => {
-                match api_passphrase_required {
-                    true => {
-                        if self.api_passphrase.is_empty()  {
-                            panic!("Unable to issue request due to API_PASSPHRASE missing while being reqiured.");
-                        }
-                    },
-                    false => {
-                        if data.contains_key("otp") {
-                            data.remove("otp");
-                        }
+            "POST" => {
+                if href.contains("private") {
+                    if !data.contains_key("nonce") {
+                        data.insert_first("nonce", self.nonce()?);
                     }
-                }
-                let json_payload = json!(data);
-                let headers = self.create_headers(href, data).await;
-                match data_empty {
-                    true => {
-                        r.headers(headers).send().await
-                    },
-                    false => {
-                        r.headers(headers).form(&json_payload).send().await
-                    },
+                    if self.api_pass_required() && !data.contains_key("otp") {
+                        data.insert("otp", self.otp_code()?);
+                    }
+                    let encoded = data.encode();
+                    headers = self.create_headers(href, data.get("nonce").unwrap().to_string(), &encoded).await?;
+                    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/x-www-form-urlencoded"));
+                    body = Some(encoded);
+                } else if !data.is_empty() {
+                    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/x-www-form-urlencoded"));
+                    body = Some(data.encode());
                 }
             },
-            false => {r.send().await},
+            _ => panic!("Error invalid method"),
         };
-        match &resp {
-            Ok(res) => {
-                if res.status() != StatusCode::OK {
-                    return Err(res.status());
-                }
-            },
-            Err(err) => {
-                if err.is_status() {
-                    return Err(err.status().unwrap());
-                } else {
-                    return Err(StatusCode::BAD_REQUEST);
-                }
+        Ok(PreparedRequest {
+            method: method.to_string(),
+            url: req_url,
+            headers,
+            body,
+        })
+    }
+
+    pub async fn construct_req<T>(&self, href: String, method: &str, data: Payload) -> Result<T, ExchangeError> 
+    where 
+        T: DeserializeOwned,
+    {
+        if href.contains("private") && self.clock.needs_refresh() {
+            self.sync_clock().await?;
+        }
+        let request = ApiRequest::new(&href, method, data);
+        let resp = match &self.cache {
+            Some(cache) => cache.get_or_fetch(&self.base_url, &request, self.send_request(request.clone())).await?,
+            None => self.send_request(request).await?,
+        };
+        decode_response(&resp)
+    }
+
+    async fn send_request(&self, request: ApiRequest) -> Result<TransportResponse, ExchangeError> {
+        match self.retry_policy.clone() {
+            Some(policy) if is_idempotent(request.endpoint()) => self.execute_with_retry(&policy, request).await,
+            _ => self.execute(request).await,
+        }
+    }
+
+    async fn execute_with_retry(&self, policy: &RetryPolicy, request: ApiRequest) -> Result<TransportResponse, ExchangeError> {
+        let mut attempt = 1;
+        loop {
+            match self.execute(request.clone()).await {
+                Err(e) if policy.should_retry(&e, attempt) => {
+                    sleep(policy.delay(attempt)).await;
+                    attempt += 1;
+                },
+                result => return result,
             }
         }
+    }
+
+    // Runs the request through the middleware chain.
+    pub async fn execute(&self, request: ApiRequest) -> Result<TransportResponse, ExchangeError> {
+        let middlewares = self.middlewares.clone();
+        Next::new(self, &middlewares).run(request).await
+    }
 
-        let r = resp.unwrap().json::<T>().await;
-        match r {
-            Ok(r) => Ok(r),
-            Err(_) => {
-                Err(StatusCode::BAD_REQUEST)
+    // The end of the middleware chain: rate limit, sign and send one attempt.
+    pub(crate) async fn dispatch(&self, request: ApiRequest) -> Result<TransportResponse, ExchangeError> {
+        let pair = match request.payload.get("pair") {
+            Some(name) if self.rate_limiter.is_some() && EndpointCost::for_endpoint(&request.href).order_rate > 0.0 => {
+                Some(self.rate_limit_pair(name))
+            },
+            _ => None,
+        };
+        self.wait_for_rate_limit(&request.href, pair.as_deref()).await?;
+        let prepared = self.prepare_req(request.href, &request.method, request.payload).await?;
+        let resp = self.transport.send(prepared).await?;
+        if resp.status != StatusCode::OK {
+            return Err(ExchangeError::Status(resp.status));
+        }
+        // Surface exchange errors here so middlewares see them; the result
+        // itself is decoded by the caller.
+        if let Ok(api_resp) = serde_json::from_slice::<APIResponse<IgnoredAny>>(&resp.body) {
+            if !api_resp.error.is_empty() {
+                return Err(ExchangeError::Api(api_resp.error));
             }
         }
+        Ok(resp)
     }
 
-    pub async fn create_headers(&mut self, href: String, data: HashMap<String, String>) -> HeaderMap {
+    pub async fn create_headers(&self, href: String, nonce: String, encoded_payload: &str) -> Result<HeaderMap, ExchangeError> {
         let mut headers = HeaderMap::new();
-        let mut nonce = Self::nonce().to_string();
+        let api_key = match self.api_pass_required() {
+            true => &self.api_key_2fa,
+            false => &self.api_key,
+        };
+        let mut api_key_val = HeaderValue::from_str(api_key).map_err(|e| ExchangeError::Config(e.to_string()))?;
+        api_key_val.set_sensitive(true);
+        headers.insert("API-Key", api_key_val);
 
-        if data.contains_key("nonce") {
-            nonce = data.get("nonce").unwrap().to_string();
+        let api_sign = self.build_signature(href, nonce, encoded_payload).await?;
+        let mut api_sign_val = HeaderValue::from_str(&api_sign).map_err(|e| ExchangeError::Signing(e.to_string()))?;
+        api_sign_val.set_sensitive(true);
+        headers.insert("API-Sign", api_sign_val);
+        Ok(headers)
+    }
+
+    // Signs with the configured signer, or in-process with the secret that
+    // belongs to the active API key. A configured signer may block, so it
+    // runs on the blocking pool.
+    pub async fn build_signature(&self, href: String, nonce: String, encoded_payload: &str) -> Result<String, ExchangeError> {
+        let path = format!("/0{}",href);
+        if let Some(signer) = self.signer.clone() {
+            let encoded_payload = encoded_payload.to_string();
+            return spawn_blocking(move || signer.sign(&path, &nonce, &encoded_payload))
+                .await
+                .map_err(|e| ExchangeError::Signing(e.to_string()))?;
         }
-        match data.is_empty() {
-            false => {
-                let api_key = match self.api_pass_required() {
-                    true => &self.api_key_2fa,
-                    false => &self.api_key,
-                };
-                let api_secret = match api_key == &self.api_key_2fa {
-                    true => &self.api_secret_2fa,
-                    false => &self.api_secret,
-                };
-                let api_key_val = HeaderValue::from_str(api_key).unwrap();
-                headers.insert("API-Key", api_key_val);
-
-                let sign_result = self.build_signature(api_secret.to_string(), href, nonce, data).await;
-                match sign_result {
-                    Ok(api_sign) => {
-                        let api_sign_val = HeaderValue::from_str(&api_sign).unwrap();
-                        headers.insert("API-Sign", api_sign_val);
-                    },
-                    Err(_) => {
-                        panic!("Error creating signature.");
-                    }
-                }        
-            },
-            true => {}
+        let api_secret = match self.api_pass_required() {
+            true => &self.api_secret_2fa,
+            false => &self.api_secret,
+        };
+        hmac_sign(api_secret, &path, &nonce, encoded_payload)
+    }
+
+    pub async fn get_server_time(&self) -> Result<ServerTime, ExchangeError> {
+        self.construct_req("/public/Time".to_string(), "GET", Payload::new()).await
+    }
+
+    // Measures the offset and round trip to the exchange's clock. The offset
+    // is shared with the default nonce provider and order timestamps.
+    pub async fn sync_clock(&self) -> Result<ClockSample, ExchangeError> {
+        let sent = SystemTime::now();
+        let resp = self.execute(ApiRequest::new("/public/Time", "GET", Payload::new())).await?;
+        let received = SystemTime::now();
+        let server_time: ServerTime = decode_response(&resp)?;
+        Ok(self.clock.record(server_time.unixtime, sent, received))
+    }
+
+    pub async fn get_system_status(&self) -> Result<SystemStatus, ExchangeError> {
+        self.construct_req("/public/SystemStatus".to_string(), "GET", Payload::new()).await
+    }
+
+    // All assets when `assets` is empty.
+    pub async fn get_assets(&self, assets: Vec<String>) -> Result<Assets, ExchangeError> {
+        let mut payload = Payload::new();
+        if !assets.is_empty() {
+            payload.insert("asset", assets.join(","));
+        }
+        self.construct_req("/public/Assets".to_string(), "GET", payload).await
+    }
+
+    // All pairs when `pairs` is empty. The result has the shape `info` selects.
+    pub async fn get_tradable_asset_pairs(&self, pairs: Vec<String>, info: AssetPairsInfo) -> Result<AssetPairs, ExchangeError> {
+        match info {
+            AssetPairsInfo::Info => self.asset_pairs(pairs, info).await.map(AssetPairs::Info),
+            AssetPairsInfo::Leverage => self.asset_pairs(pairs, info).await.map(AssetPairs::Leverage),
+            AssetPairsInfo::Fees => self.asset_pairs(pairs, info).await.map(AssetPairs::Fees),
+            AssetPairsInfo::Margin => self.asset_pairs(pairs, info).await.map(AssetPairs::Margin),
+        }
+    }
+
+    async fn asset_pairs<T: DeserializeOwned>(&self, pairs: Vec<String>, info: AssetPairsInfo) -> Result<HashMap<String, T>, ExchangeError> {
+        let mut payload = Payload::new();
+        if !pairs.is_empty() {
+            payload.insert("pair", pairs.join(","));
+        }
+        if info != AssetPairsInfo::Info {
+            payload.insert("info", info.to_string());
+        }
+        self.construct_req("/public/AssetPairs".to_string(), "GET", payload).await
+    }
+
+    // Every asset and pair, for resolving names with `AssetCatalog`.
+    pub async fn get_catalog(&self) -> Result<AssetCatalog, ExchangeError> {
+        let assets = self.get_assets(Vec::new()).await?;
+        let pairs: TradingPairs = self.asset_pairs(Vec::new(), AssetPairsInfo::Info).await?;
+        Ok(AssetCatalog::new(&assets, &pairs))
+    }
+
+    // The catalog fetched by the first call and kept for later ones. Clones
+    // share it, and concurrent first calls fetch it once.
+    pub async fn catalog(&self) -> Result<Arc<AssetCatalog>, ExchangeError> {
+        if let Some(catalog) = self.loaded_catalog() {
+            return Ok(catalog);
+        }
+        let _fetch = self.catalog_fetch.lock().await;
+        // The fetch this call waited for may have loaded it.
+        if let Some(catalog) = self.loaded_catalog() {
+            return Ok(catalog);
         }
-        headers
+        self.fetch_catalog().await
+    }
+
+    // Replaces the kept catalog, e.g. after pairs are listed or delisted.
+    pub async fn refresh_catalog(&self) -> Result<Arc<AssetCatalog>, ExchangeError> {
+        let _fetch = self.catalog_fetch.lock().await;
+        self.fetch_catalog().await
+    }
+
+    fn loaded_catalog(&self) -> Option<Arc<AssetCatalog>> {
+        self.catalog.read().expect("Catalog lock poisoned.").clone()
     }
 
-    pub async fn build_signature(&self, secret: String, href: String, nonce: String, payload: HashMap<String, String>) -> Result<String, Box<dyn std::error::Error>> {
-        let href = format!("/0{}",href);
-        let mut encoded_payload: String = String::from("");
-        let mut arguments: Vec<String> = vec![format!("nonce={}",nonce)];
-        for (key, value) in payload.into_iter() {
-            if &key != "nonce" {
-                arguments.push(format!("{}={}",key,urlencoding::encode(&value)));
+    async fn fetch_catalog(&self) -> Result<Arc<AssetCatalog>, ExchangeError> {
+        let catalog = Arc::new(self.get_catalog().await?);
+        *self.catalog.write().expect("Catalog lock poisoned.") = Some(catalog.clone());
+        Ok(catalog)
+    }
+
+    // All pairs when `pairs` is empty.
+    pub async fn get_ticker(&self, pairs: Vec<String>) -> Result<Tickers, ExchangeError> {
+        let mut payload = Payload::new();
+        if !pairs.is_empty() {
+            payload.insert("pair", pairs.join(","));
+        }
+        self.construct_req("/public/Ticker".to_string(), "GET", payload).await
+    }
+
+    pub async fn get_balance(&self) -> Result<Balances, ExchangeError> {
+        self.construct_req("/private/Balance".to_string(), "POST", Payload::new()).await
+    }
+
+    pub async fn get_open_orders(&self) -> Result<OpenOrders, ExchangeError> {
+        self.construct_req("/private/OpenOrders".to_string(), "POST", Payload::new()).await
+    }
+
+    // Looks for an order with the given client order id among open orders,
+    // then closed ones.
+    pub async fn find_order(&self, cl_ord_id: &str) -> Result<Option<(String, OrderSummary)>, ExchangeError> {
+        for href in ["/private/OpenOrders", "/private/ClosedOrders"] {
+            let mut payload = Payload::new();
+            payload.insert("cl_ord_id", cl_ord_id);
+            let lookup: OrderLookup = self.construct_req(href.to_string(), "POST", payload).await?;
+            if let Some(found) = lookup.find(cl_ord_id) {
+                return Ok(Some(found));
             }
         }
-        encoded_payload = arguments.join("&");
+        Ok(None)
+    }
 
-        let mut hashed_payload: Vec<u8> = Vec::new();
-        let mut sha_digest: Sha256 = Sha256::default();
-        sha_digest.update(nonce.to_string());
-        sha_digest.update(encoded_payload);
-        hashed_payload = sha_digest.finalize().to_vec();
-        
-        let secret_bytes: Vec<u8> = decode(&secret).expect("Error decoding api_secret.");
-        let mut hmac_512: Hmac<Sha512> = Hmac::<Sha512>::new_varkey(&secret_bytes).expect("Error creating Hmac<Sha512>.");
-        hmac_512.update(href.as_bytes());
-        hmac_512.update(&hashed_payload);
-        
-        Ok(encode(hmac_512.finalize().into_bytes()))
+    // Looks for an order with the given userref opened at or after `since`,
+    // on the server's clock, among open orders, then closed ones. More than
+    // one match is an error, as it cannot tell which order is meant.
+    pub async fn find_order_by_userref(&self, userref: i32, since: Timestamp) -> Result<Option<(String, OrderSummary)>, ExchangeError> {
+        for href in ["/private/OpenOrders", "/private/ClosedOrders"] {
+            let mut payload = Payload::new();
+            payload.insert("userref", userref);
+            let lookup: OrderLookup = self.construct_req(href.to_string(), "POST", payload).await?;
+            let mut found = lookup.find_userref(userref, since);
+            match found.len() {
+                0 => continue,
+                1 => return Ok(found.pop()),
+                n => return Err(ExchangeError::InvalidOrder(format!("{} orders with userref {} were opened since {}.", n, userref, since))),
+            }
+        }
+        Ok(None)
     }
 
-    pub async fn get_server_time(&mut self) -> Result<APIResponse, reqwest::StatusCode> {
-        let response: Result<APIResponse, reqwest::StatusCode> = self.construct_req("/public/Time".to_string(), "GET", HashMap::new()).await;
-        response
+    // With a retry policy, the order gets a client order id, unless it has a
+    // userref: the exchange does not accept both. After a failure that leaves
+    // it unclear whether the order landed, the order is looked up by that id,
+    // or by its userref among orders opened since the first attempt, and only
+    // re-sent if it is not found. If the lookup fails too, the original error
+    // is returned rather than risking a duplicate.
+    //
+    // When the loaded catalog knows the pair, the order is checked against
+    // its rules first and refused here if the exchange would refuse it.
+    pub async fn add_order(&self, mut order: NewOrder) -> Result<AddOrderResult, ExchangeError> {
+        if order.has_absolute_time() && (self.clock.synced_at().is_none() || self.clock.needs_refresh()) {
+            self.sync_clock().await?;
+        }
+        if self.rate_limiter.is_some() {
+            self.load_catalog_for_rate_limit().await;
+        }
+        if let Some(catalog) = self.loaded_catalog() {
+            if let Ok(pair) = catalog.pair_info(&order.pair) {
+                order = order.validated(pair, Rounding::Reject)?;
+            }
+        }
+        let result = match self.retry_policy.clone() {
+            Some(policy) if !order.validate => self.add_order_with_retry(&policy, &mut order).await?,
+            _ => {
+                let payload = order.to_payload(&self.clock.offset);
+                self.construct_req("/private/AddOrder".to_string(), "POST", payload).await?
+            },
+        };
+        self.record_order(&order, &result).await;
+        Ok(result)
+    }
+
+    async fn add_order_with_retry(&self, policy: &RetryPolicy, order: &mut NewOrder) -> Result<AddOrderResult, ExchangeError> {
+        // A second of slack for the clock offset, which is accurate to about 500ms.
+        let since = Timestamp::from(self.clock.offset.server_now() - Duration::from_secs(1));
+        let order_ref = match (order.userref, &order.cl_ord_id) {
+            (_, Some(cl_ord_id)) => OrderRef::ClOrdId(cl_ord_id.clone()),
+            (Some(userref), None) => OrderRef::Userref(userref),
+            #[cfg(feature = "order-ids")]
+            (None, None) => OrderRef::ClOrdId(order.cl_ord_id.insert(Uuid::new_v4().to_string()).clone()),
+            // Without an id to look it up by, the order is sent once.
+            #[cfg(not(feature = "order-ids"))]
+            (None, None) => {
+                let payload = order.to_payload(&self.clock.offset);
+                return self.construct_req("/private/AddOrder".to_string(), "POST", payload).await;
+            },
+        };
+
+        let mut attempt = 1;
+        loop {
+            let payload = order.to_payload(&self.clock.offset);
+            let err = match self.construct_req("/private/AddOrder".to_string(), "POST", payload).await {
+                Ok(result) => return Ok(result),
+                Err(e) => e,
+            };
+            if !policy.should_retry(&err, attempt) {
+                return Err(err);
+            }
+            sleep(policy.delay(attempt)).await;
+            if ErrorClass::of(&err).is_some_and(|class| class.is_ambiguous()) {
+                let found = match &order_ref {
+                    OrderRef::ClOrdId(cl_ord_id) => self.find_order(cl_ord_id).await,
+                    OrderRef::Userref(userref) => self.find_order_by_userref(*userref, since).await,
+                };
+                match found {
+                    Ok(Some((txid, summary))) => {
+                        return Ok(AddOrderResult {
+                            descr: AddOrderDescription {
+                                order: summary.descr.order,
+                                close: summary.descr.close,
+                            },
+                            txid: vec![txid],
+                        });
+                    },
+                    Ok(None) => {},
+                    Err(lookup_err) => {
+                        log::warn!("Not re-sending order {}: lookup failed after {}: {}", order_ref, err, lookup_err);
+                        return Err(err);
+                    },
+                }
+            }
+            attempt += 1;
+        }
     }
 
-    pub async fn get_tradable_asset_pairs(&mut self, pairs: Vec<String>, info: Option<String>) -> Result<TradingPairResponse, reqwest::StatusCode> {
-        let mut payload: HashMap<String, String> = HashMap::new();
-        payload.insert("pair".to_string(), pairs.join(","));
-        if let Some(i) = info {
-            payload.insert("info".to_string(), i);
+    // Lets the rate limiter charge the cancel penalty if the order is
+    // cancelled through this exchange, by txid or client order id.
+    async fn record_order(&self, order: &NewOrder, result: &AddOrderResult) {
+        let limiter = match &self.rate_limiter {
+            Some(limiter) if !result.txid.is_empty() => limiter,
+            _ => return,
+        };
+        let pair = self.rate_limit_pair(&order.pair);
+        let ids = result.txid.iter().cloned().chain(order.cl_ord_id.clone()).collect();
+        limiter.lock().expect("RateLimiter lock poisoned.").record_order(ids, &pair);
+    }
+
+    // Cancels by txid, userref or client order id.
+    pub async fn cancel_order(&self, txid: &str) -> Result<CancelOrderResult, ExchangeError> {
+        let mut payload = Payload::new();
+        payload.insert("txid", txid);
+        let result: CancelOrderResult = self.construct_req("/private/CancelOrder".to_string(), "POST", payload).await?;
+        if let (Some(limiter), true) = (&self.rate_limiter, result.count > 0) {
+            limiter.lock().expect("RateLimiter lock poisoned.").record_cancel_of(txid);
         }
-        let response: Result<TradingPairResponse, reqwest::StatusCode> = self.construct_req("/public/AssetPairs".to_string(), "GET", payload).await;
-        response
+        Ok(result)
+    }
+
+    // The most recent trades first, 50 at a time; `start` excludes older ones.
+    pub async fn get_trades_history(&self, start: Option<Timestamp>) -> Result<TradesHistory, ExchangeError> {
+        self.get_trades_history_page(start, 0).await
     }
 
-    pub async fn get_open_orders(&mut self) -> Result<APIResponse, reqwest::StatusCode> {
-        let mut payload: HashMap<String, String> = HashMap::new();
-        let api_passphrase = &self.api_passphrase;
-        
-        payload.insert("nonce".to_string(), Self::nonce().to_string());
-        if !api_passphrase.is_empty() {
-            payload.insert("otp".to_string(), api_passphrase.to_string());
+    // The page of trades after the first `ofs`; `count` says how many match.
+    pub async fn get_trades_history_page(&self, start: Option<Timestamp>, ofs: usize) -> Result<TradesHistory, ExchangeError> {
+        let mut payload = Payload::new();
+        if let Some(start) = start {
+            payload.insert("start", start);
+        }
+        if ofs > 0 {
+            payload.insert("ofs", ofs);
         }
+        self.construct_req("/private/TradesHistory".to_string(), "POST", payload).await
+    }
+}
 
-        let response: Result<APIResponse, reqwest::StatusCode> = self.construct_req("/private/OpenOrders".to_string(), "POST", payload).await;
-        response
+// How an order sent with retries is found again.
+enum OrderRef {
+    ClOrdId(String),
+    Userref(i32),
+}
+
+impl fmt::Display for OrderRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrderRef::ClOrdId(cl_ord_id) => write!(f, "{}", cl_ord_id),
+            OrderRef::Userref(userref) => write!(f, "with userref {}", userref),
+        }
     }
-}
\ No newline at end of file
+}
+
+fn decode_response<T>(resp: &TransportResponse) -> Result<T, ExchangeError>
+where
+    T: DeserializeOwned,
+{
+    match serde_json::from_slice::<APIResponse<T>>(&resp.body) {
+        Ok(r) => r.into_result(),
+        Err(err) => Err(ExchangeError::Decode(err.to_string())),
+    }
+}
@@ -1,8 +1,37 @@
-extern crate cucumber;
 extern crate serde;
 extern crate serde_json;
+pub mod assets;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod cache;
+pub mod clock;
+#[cfg(feature = "config")]
+pub mod config;
+pub mod encoding;
+pub mod errors;
 pub mod responses;
+pub mod retry;
+pub mod secrets;
+pub mod signer;
+#[cfg(feature = "futures")]
+pub mod streams;
 pub mod exchanges;
+pub mod middleware;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod nonce;
 pub mod orders;
+pub mod otp;
+pub mod rate_limit;
 pub mod system_server;
-pub mod trades;
\ No newline at end of file
+pub mod timestamp;
+pub mod trades;
+pub mod transport;
+pub mod unified;
+#[cfg(feature = "vault")]
+pub mod vault;
+#[cfg(feature = "websocket")]
+pub mod websocket;
+
+// Prices, volumes and fees are exact decimals.
+pub use rust_decimal::Decimal;
@@ -0,0 +1,246 @@
+use crate::{
+    encoding::Payload,
+    errors::ExchangeError,
+    exchanges::BaseExchange,
+    retry::{
+        is_idempotent,
+        RetryPolicy,
+    },
+    transport::TransportResponse,
+};
+
+use async_trait::async_trait;
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{
+        Arc,
+        Mutex,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use tokio::time::sleep;
+
+
+
+/* API Request */
+// A call before it is signed. Changes made by middlewares are part of the
+// signed body, and every pass down the chain gets a fresh nonce.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiRequest {
+    pub href: String,
+    pub method: String,
+    pub payload: Payload,
+}
+
+impl ApiRequest {
+    pub fn new(href: &str, method: &str, payload: Payload) -> Self {
+        Self {
+            href: href.to_string(),
+            method: method.to_string(),
+            payload,
+        }
+    }
+
+    pub fn is_private(&self) -> bool {
+        self.href.contains("private")
+    }
+
+    // The last path segment, e.g. `AddOrder`.
+    pub fn endpoint(&self) -> &str {
+        self.href.rsplit('/').next().unwrap_or("")
+    }
+}
+
+/* Middleware */
+// Wraps every call made by `BaseExchange::construct_req`. Call `next.run` to
+// continue down the chain (any number of times), or return without calling
+// it to short-circuit. The response handed back has a 200 status and no
+// exchange errors; anything else arrives as an `Err`.
+#[async_trait]
+pub trait Middleware: fmt::Debug + Send + Sync {
+    async fn handle(&self, request: ApiRequest, next: &mut Next<'_>) -> Result<TransportResponse, ExchangeError>;
+}
+
+/* Chain Position */
+pub struct Next<'a> {
+    exchange: &'a BaseExchange,
+    rest: &'a [Arc<dyn Middleware>],
+}
+
+impl<'a> Next<'a> {
+    pub(crate) fn new(exchange: &'a BaseExchange, rest: &'a [Arc<dyn Middleware>]) -> Self {
+        Self {
+            exchange,
+            rest,
+        }
+    }
+
+    pub async fn run(&mut self, request: ApiRequest) -> Result<TransportResponse, ExchangeError> {
+        match self.rest.split_first() {
+            Some((first, rest)) => {
+                let mut next = Next::new(self.exchange, rest);
+                first.handle(request, &mut next).await
+            },
+            None => self.exchange.dispatch(request).await,
+        }
+    }
+}
+
+/* Logging */
+// One line per call: method, endpoint, parameters, outcome and latency.
+// Values of the `redact` keys are replaced; API keys and signatures are only
+// added after the chain and never reach it.
+#[derive(Debug, Clone)]
+pub struct LoggingMiddleware {
+    pub level: log::Level,
+    pub redact: Vec<String>,
+}
+
+impl Default for LoggingMiddleware {
+    fn default() -> Self {
+        Self {
+            level: log::Level::Info,
+            redact: vec!["otp".to_string(), "password".to_string()],
+        }
+    }
+}
+
+impl LoggingMiddleware {
+    pub fn params(&self, payload: &Payload) -> String {
+        payload
+            .iter()
+            .map(|(key, val)| match self.redact.iter().any(|r| r == key) {
+                true => format!("{}=***", key),
+                false => format!("{}={}", key, val),
+            })
+            .collect::<Vec<String>>()
+            .join("&")
+    }
+}
+
+#[async_trait]
+impl Middleware for LoggingMiddleware {
+    async fn handle(&self, request: ApiRequest, next: &mut Next<'_>) -> Result<TransportResponse, ExchangeError> {
+        let method = request.method.clone();
+        let href = request.href.clone();
+        let params = self.params(&request.payload);
+        let started = Instant::now();
+        let result = next.run(request).await;
+        let elapsed_ms = started.elapsed().as_millis();
+        match &result {
+            Ok(resp) => log::log!(
+                self.level,
+                "method={} href={} params={:?} status={} bytes={} elapsed_ms={}",
+                method, href, params, resp.status.as_u16(), resp.body.len(), elapsed_ms,
+            ),
+            Err(e) => log::warn!(
+                "method={} href={} params={:?} error={:?} elapsed_ms={}",
+                method, href, params, e.to_string(), elapsed_ms,
+            ),
+        }
+        result
+    }
+}
+
+/* Latency Metrics */
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EndpointMetrics {
+    pub calls: u64,
+    pub errors: u64,
+    pub total: Duration,
+    pub max: Duration,
+}
+
+impl EndpointMetrics {
+    pub fn mean(&self) -> Duration {
+        match self.calls {
+            0 => Duration::ZERO,
+            calls => self.total / calls as u32,
+        }
+    }
+}
+
+// Per-endpoint call counts and latencies. Keep an `Arc` to read them back.
+#[derive(Debug, Default)]
+pub struct MetricsMiddleware {
+    endpoints: Mutex<HashMap<String, EndpointMetrics>>,
+}
+
+impl MetricsMiddleware {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn snapshot(&self) -> HashMap<String, EndpointMetrics> {
+        self.endpoints.lock().expect("MetricsMiddleware lock poisoned.").clone()
+    }
+
+    pub fn record(&self, href: &str, elapsed: Duration, failed: bool) {
+        let mut endpoints = self.endpoints.lock().expect("MetricsMiddleware lock poisoned.");
+        let metrics = endpoints.entry(href.to_string()).or_default();
+        metrics.calls += 1;
+        if failed {
+            metrics.errors += 1;
+        }
+        metrics.total += elapsed;
+        metrics.max = metrics.max.max(elapsed);
+    }
+}
+
+#[async_trait]
+impl Middleware for MetricsMiddleware {
+    async fn handle(&self, request: ApiRequest, next: &mut Next<'_>) -> Result<TransportResponse, ExchangeError> {
+        let href = request.href.clone();
+        let started = Instant::now();
+        let result = next.run(request).await;
+        self.record(&href, started.elapsed(), result.is_err());
+        result
+    }
+}
+
+/* Retry */
+// Retries idempotent calls under a `RetryPolicy`. Each attempt goes back down
+// the chain and is signed with a fresh nonce. Order placement and other
+// non-idempotent calls pass through untouched; `BaseExchange::retry_policy`
+// retries `add_order` safely instead. When the exchange has a retry policy,
+// that policy retries every call and this middleware passes them through, so
+// attempts are never multiplied by a second retry layer.
+#[derive(Debug, Clone, Default)]
+pub struct RetryMiddleware {
+    pub policy: RetryPolicy,
+}
+
+impl RetryMiddleware {
+    pub fn new(policy: RetryPolicy) -> Self {
+        Self {
+            policy,
+        }
+    }
+}
+
+#[async_trait]
+impl Middleware for RetryMiddleware {
+    async fn handle(&self, request: ApiRequest, next: &mut Next<'_>) -> Result<TransportResponse, ExchangeError> {
+        if !is_idempotent(request.endpoint()) || next.exchange.retry_policy.is_some() {
+            return next.run(request).await;
+        }
+        let mut attempt = 1;
+        loop {
+            match next.run(request.clone()).await {
+                Err(e) if self.policy.should_retry(&e, attempt) => {
+                    let delay = self.policy.delay(attempt);
+                    log::debug!("Retrying {} after {:?} (attempt {} failed: {}).", request.href, delay, attempt, e);
+                    sleep(delay).await;
+                    attempt += 1;
+                },
+                result => return result,
+            }
+        }
+    }
+}
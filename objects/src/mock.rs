@@ -0,0 +1,80 @@
+use crate::{
+    errors::ExchangeError,
+    exchanges::PreparedRequest,
+    transport::{
+        Transport,
+        TransportResponse,
+    },
+};
+
+use async_trait::async_trait;
+
+use http::StatusCode;
+
+use std::{
+    collections::{
+        HashMap,
+        VecDeque,
+    },
+    sync::Mutex,
+};
+
+
+
+/* In-Memory Transport */
+// Answers from canned responses keyed by URL path (query excluded) and keeps
+// every request it was given, for tests that run without a network. Queued
+// responses are returned in order; the last one keeps being returned.
+#[derive(Debug, Default)]
+pub struct MemoryTransport {
+    responses: Mutex<HashMap<String, VecDeque<TransportResponse>>>,
+    requests: Mutex<Vec<PreparedRequest>>,
+}
+
+impl MemoryTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn respond(&self, path: &str, response: TransportResponse) {
+        let mut responses = self.responses.lock().expect("MemoryTransport lock poisoned.");
+        responses.entry(path.to_string()).or_default().push_back(response);
+    }
+
+    // Queues a 200 response with the given JSON body.
+    pub fn respond_json(&self, path: &str, body: &str) {
+        self.respond(path, TransportResponse::new(StatusCode::OK, body));
+    }
+
+    pub fn requests(&self) -> Vec<PreparedRequest> {
+        self.requests.lock().expect("MemoryTransport lock poisoned.").clone()
+    }
+}
+
+#[async_trait]
+impl Transport for MemoryTransport {
+    async fn send(&self, request: PreparedRequest) -> Result<TransportResponse, ExchangeError> {
+        let path = url_path(&request.url);
+        self.requests.lock().expect("MemoryTransport lock poisoned.").push(request);
+
+        let mut responses = self.responses.lock().expect("MemoryTransport lock poisoned.");
+        match responses.get_mut(&path) {
+            Some(queue) if queue.len() > 1 => Ok(queue.pop_front().unwrap()),
+            Some(queue) if !queue.is_empty() => Ok(queue[0].clone()),
+            _ => Err(ExchangeError::Request(format!("No response configured for {}.", path))),
+        }
+    }
+}
+
+// `https://host/0/public/Time?x=1` -> `/0/public/Time`
+fn url_path(url: &str) -> String {
+    let without_query = url.split('?').next().unwrap_or("");
+    let after_scheme = match without_query.split_once("://") {
+        Some((_, rest)) => rest,
+        None => without_query,
+    };
+    match after_scheme.find('/') {
+        Some(i) => after_scheme[i..].to_string(),
+        None => "/".to_string(),
+    }
+}
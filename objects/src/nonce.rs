@@ -0,0 +1,163 @@
+use crate::{
+    clock::ClockOffset,
+    errors::ExchangeError,
+};
+
+#[cfg(feature = "file-nonce")]
+use fs2::FileExt;
+
+use std::{
+    fmt,
+    sync::atomic::{
+        AtomicU64,
+        Ordering,
+    },
+    time::{
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+
+#[cfg(feature = "file-nonce")]
+use std::{
+    fs::OpenOptions,
+    io::{
+        Read,
+        Seek,
+        SeekFrom,
+        Write,
+    },
+    path::PathBuf,
+};
+
+
+
+/* Nonce Resolution */
+// The exchange only requires nonces to increase per key, so a key can move to
+// a finer resolution later but never back to a coarser one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NonceResolution {
+    #[default]
+    Millis,
+    Micros,
+    Nanos,
+}
+
+impl NonceResolution {
+    pub fn now(&self) -> u64 {
+        self.at(SystemTime::now())
+    }
+
+    pub fn at(&self, time: SystemTime) -> u64 {
+        let since_epoch = time.duration_since(UNIX_EPOCH).expect("Error creating since_epoch");
+        match self {
+            NonceResolution::Millis => since_epoch.as_millis() as u64,
+            NonceResolution::Micros => since_epoch.as_micros() as u64,
+            NonceResolution::Nanos => since_epoch.as_nanos() as u64,
+        }
+    }
+}
+
+/* Nonce Provider */
+pub trait NonceProvider: fmt::Debug + Send + Sync {
+    // Returns a nonce strictly greater than every nonce this provider has issued.
+    fn next_nonce(&self) -> Result<u64, ExchangeError>;
+}
+
+/* In-Process Provider */
+// Clock-based nonce that never repeats within a process, even when several
+// tasks ask for one in the same clock tick.
+#[derive(Debug, Default)]
+pub struct IncreasingNonce {
+    resolution: NonceResolution,
+    last: AtomicU64,
+    clock_offset: Option<ClockOffset>,
+}
+
+impl IncreasingNonce {
+    pub fn new(resolution: NonceResolution) -> Self {
+        Self {
+            resolution,
+            last: AtomicU64::new(0),
+            clock_offset: None,
+        }
+    }
+
+    // Stamps nonces with the server's clock instead of the local one.
+    pub fn with_clock_offset(mut self, clock_offset: ClockOffset) -> Self {
+        self.clock_offset = Some(clock_offset);
+        self
+    }
+
+    fn now(&self) -> u64 {
+        match &self.clock_offset {
+            Some(offset) => self.resolution.at(offset.server_now()),
+            None => self.resolution.now(),
+        }
+    }
+}
+
+impl NonceProvider for IncreasingNonce {
+    fn next_nonce(&self) -> Result<u64, ExchangeError> {
+        let mut last = self.last.load(Ordering::SeqCst);
+        loop {
+            let next = self.now().max(last + 1);
+            match self.last.compare_exchange(last, next, Ordering::SeqCst, Ordering::SeqCst) {
+                Ok(_) => return Ok(next),
+                Err(current) => last = current,
+            }
+        }
+    }
+}
+
+/* Persistent Provider */
+// Stores the last issued nonce in a file guarded by an exclusive lock, so
+// several processes sharing one key never send the same or a lower nonce.
+#[cfg(feature = "file-nonce")]
+#[derive(Debug)]
+pub struct FileNonce {
+    path: PathBuf,
+    resolution: NonceResolution,
+}
+
+#[cfg(feature = "file-nonce")]
+impl FileNonce {
+    pub fn new(path: impl Into<PathBuf>, resolution: NonceResolution) -> Self {
+        Self {
+            path: path.into(),
+            resolution,
+        }
+    }
+}
+
+#[cfg(feature = "file-nonce")]
+impl NonceProvider for FileNonce {
+    fn next_nonce(&self) -> Result<u64, ExchangeError> {
+        let nonce_err = |err: std::io::Error| ExchangeError::Nonce(format!("{}: {}", self.path.display(), err));
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&self.path)
+            .map_err(nonce_err)?;
+        file.lock_exclusive().map_err(nonce_err)?;
+
+        let mut contents = String::new();
+        file.read_to_string(&mut contents).map_err(nonce_err)?;
+        let last: u64 = match contents.trim().is_empty() {
+            true => 0,
+            false => contents.trim().parse().map_err(|_| {
+                ExchangeError::Nonce(format!("{}: invalid nonce {:?}", self.path.display(), contents.trim()))
+            })?,
+        };
+        let next = self.resolution.now().max(last + 1);
+
+        file.set_len(0).map_err(nonce_err)?;
+        file.seek(SeekFrom::Start(0)).map_err(nonce_err)?;
+        file.write_all(next.to_string().as_bytes()).map_err(nonce_err)?;
+        file.sync_data().map_err(nonce_err)?;
+        file.unlock().map_err(nonce_err)?;
+        Ok(next)
+    }
+}
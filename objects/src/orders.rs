@@ -1,44 +1,763 @@
+use crate::{
+    clock::ClockOffset,
+    encoding::Payload,
+    errors::ExchangeError,
+    timestamp::{
+        zero_as_none,
+        Timestamp,
+    },
+    trades::TradingPair,
+};
+
+use rust_decimal::{
+    Decimal,
+    RoundingStrategy,
+};
+
 use serde::{
     Deserialize,
-    Serialize
+    Deserializer,
+    Serialize,
+    Serializer,
 };
 
-use std::collections::HashMap;
+use std::{
+    collections::{
+        BTreeSet,
+        HashMap,
+    },
+    fmt,
+    str::FromStr,
+    time::{
+        Duration,
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+
+/* Order Side */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Side::Buy => write!(f, "buy"),
+            Side::Sell => write!(f, "sell"),
+        }
+    }
+}
+
+/* Order Type */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OrderType {
+    Market,
+    Limit,
+    Iceberg,
+    StopLoss,
+    TakeProfit,
+    StopLossLimit,
+    TakeProfitLimit,
+    TrailingStop,
+    TrailingStopLimit,
+    SettlePosition,
+}
+
+impl fmt::Display for OrderType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            OrderType::Market => "market",
+            OrderType::Limit => "limit",
+            OrderType::Iceberg => "iceberg",
+            OrderType::StopLoss => "stop-loss",
+            OrderType::TakeProfit => "take-profit",
+            OrderType::StopLossLimit => "stop-loss-limit",
+            OrderType::TakeProfitLimit => "take-profit-limit",
+            OrderType::TrailingStop => "trailing-stop",
+            OrderType::TrailingStopLimit => "trailing-stop-limit",
+            OrderType::SettlePosition => "settle-position",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for OrderType {
+    type Err = String;
+
+    fn from_str(ordertype: &str) -> Result<Self, Self::Err> {
+        match ordertype {
+            "market" => Ok(OrderType::Market),
+            "limit" => Ok(OrderType::Limit),
+            "iceberg" => Ok(OrderType::Iceberg),
+            "stop-loss" => Ok(OrderType::StopLoss),
+            "take-profit" => Ok(OrderType::TakeProfit),
+            "stop-loss-limit" => Ok(OrderType::StopLossLimit),
+            "take-profit-limit" => Ok(OrderType::TakeProfitLimit),
+            "trailing-stop" => Ok(OrderType::TrailingStop),
+            "trailing-stop-limit" => Ok(OrderType::TrailingStopLimit),
+            "settle-position" => Ok(OrderType::SettlePosition),
+            _ => Err(format!("Unknown order type {}.", ordertype)),
+        }
+    }
+}
+
+/* Order Status */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderStatus {
+    Pending,
+    Open,
+    Closed,
+    Canceled,
+    Expired,
+}
+
+/* Trigger */
+// The price that triggers stop and take-profit orders.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Trigger {
+    #[default]
+    Last,
+    Index,
+}
+
+/* Flags */
+// `oflags` on orders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum OrderFlag {
+    // Post-only: only ever adds liquidity.
+    Post,
+    // Take the fee in the base currency.
+    Fcib,
+    // Take the fee in the quote currency.
+    Fciq,
+    // No market price protection.
+    Nompp,
+    // Volume is in the quote currency.
+    Viqc,
+}
+
+impl fmt::Display for OrderFlag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            OrderFlag::Post => "post",
+            OrderFlag::Fcib => "fcib",
+            OrderFlag::Fciq => "fciq",
+            OrderFlag::Nompp => "nompp",
+            OrderFlag::Viqc => "viqc",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for OrderFlag {
+    type Err = String;
+
+    fn from_str(flag: &str) -> Result<Self, Self::Err> {
+        match flag {
+            "post" => Ok(OrderFlag::Post),
+            "fcib" => Ok(OrderFlag::Fcib),
+            "fciq" => Ok(OrderFlag::Fciq),
+            "nompp" => Ok(OrderFlag::Nompp),
+            "viqc" => Ok(OrderFlag::Viqc),
+            _ => Err(format!("Unknown order flag {}.", flag)),
+        }
+    }
+}
+
+// `misc` on orders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MiscFlag {
+    Stopped,
+    Touched,
+    Liquidated,
+    Partial,
+    Amended,
+}
+
+impl fmt::Display for MiscFlag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            MiscFlag::Stopped => "stopped",
+            MiscFlag::Touched => "touched",
+            MiscFlag::Liquidated => "liquidated",
+            MiscFlag::Partial => "partial",
+            MiscFlag::Amended => "amended",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for MiscFlag {
+    type Err = String;
+
+    fn from_str(flag: &str) -> Result<Self, Self::Err> {
+        match flag {
+            "stopped" => Ok(MiscFlag::Stopped),
+            "touched" => Ok(MiscFlag::Touched),
+            "liquidated" => Ok(MiscFlag::Liquidated),
+            "partial" => Ok(MiscFlag::Partial),
+            "amended" => Ok(MiscFlag::Amended),
+            _ => Err(format!("Unknown misc flag {}.", flag)),
+        }
+    }
+}
+
+/* Flag Set */
+// A comma separated list of flags, e.g. `post,fciq`. Flags this crate does
+// not know yet are skipped when reading a response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlagSet<F: Ord>(BTreeSet<F>);
+
+pub type OrderFlags = FlagSet<OrderFlag>;
+pub type MiscFlags = FlagSet<MiscFlag>;
+
+impl<F: Ord> Default for FlagSet<F> {
+    fn default() -> Self {
+        Self(BTreeSet::new())
+    }
+}
+
+impl<F: Ord> FlagSet<F> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, flag: F) -> bool {
+        self.0.insert(flag)
+    }
+
+    pub fn contains(&self, flag: &F) -> bool {
+        self.0.contains(flag)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &F> {
+        self.0.iter()
+    }
+}
+
+impl<F: Ord> FromIterator<F> for FlagSet<F> {
+    fn from_iter<I: IntoIterator<Item = F>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<F: Ord + fmt::Display> fmt::Display for FlagSet<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let flags: Vec<String> = self.0.iter().map(|flag| flag.to_string()).collect();
+        write!(f, "{}", flags.join(","))
+    }
+}
+
+impl<F: Ord + FromStr> FlagSet<F> {
+    pub fn parse(flags: &str) -> Self {
+        flags
+            .split(',')
+            .map(str::trim)
+            .filter(|flag| !flag.is_empty())
+            .filter_map(|flag| match flag.parse() {
+                Ok(flag) => Some(flag),
+                Err(_) => {
+                    log::debug!("Skipping unknown flag {}.", flag);
+                    None
+                },
+            })
+            .collect()
+    }
+}
+
+impl<F: Ord + fmt::Display> Serialize for FlagSet<F> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
 
+impl<'de, F: Ord + FromStr> Deserialize<'de> for FlagSet<F> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(FlagSet::parse(&String::deserialize(deserializer)?))
+    }
+}
+
+/* Orders */
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OpenOrders {
     pub open: HashMap<String, Order>
 }
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Order {
-    pub refid: String,
-    pub userref: String,
-    pub status: String,
-    pub opentm: i64,
-    pub start_tm: i64,
-    pub expire_tm: i64,
+    pub refid: Option<String>,
+    #[serde(default)]
+    pub userref: Option<i64>,
+    #[serde(default)]
+    pub cl_ord_id: Option<String>,
+    pub status: OrderStatus,
+    pub opentm: Timestamp,
+    #[serde(default, deserialize_with = "zero_as_none")]
+    pub starttm: Option<Timestamp>,
+    #[serde(default, deserialize_with = "zero_as_none")]
+    pub expiretm: Option<Timestamp>,
+    // Closed orders only.
+    #[serde(default, deserialize_with = "zero_as_none")]
+    pub closetm: Option<Timestamp>,
+    #[serde(default)]
+    pub reason: Option<String>,
     pub descr: OrderInfo,
-    pub vol: String,
-    pub vol_exec: String,
-    pub cost: String,
-    pub fee: String,
-    pub price: String,
-    pub stopprice: String,
-    pub limitprice: String,
-    pub trigger: String,
-    pub misc: String,
-    pub oflags: String,
-    pub trades: Vec<String>,
+    pub vol: Decimal,
+    pub vol_exec: Decimal,
+    pub cost: Decimal,
+    pub fee: Decimal,
+    // Average price of the executed volume.
+    pub price: Decimal,
+    #[serde(default)]
+    pub stopprice: Option<Decimal>,
+    #[serde(default)]
+    pub limitprice: Option<Decimal>,
+    #[serde(default)]
+    pub trigger: Option<Trigger>,
+    #[serde(default)]
+    pub margin: Option<bool>,
+    #[serde(default)]
+    pub misc: MiscFlags,
+    #[serde(default)]
+    pub oflags: OrderFlags,
+    #[serde(default)]
+    pub trades: Option<Vec<String>>,
+}
+
+impl Order {
+    // Volume not executed yet.
+    pub fn remaining(&self) -> Decimal {
+        self.vol - self.vol_exec
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OrderInfo {
     pub pair: String,
-    pub r#type: String,
-    pub ordertype: String,
-    pub price: String,
-    pub price2: String,
+    pub r#type: Side,
+    pub ordertype: OrderType,
+    pub price: Decimal,
+    pub price2: Decimal,
     pub leverage: String,
     pub order: String,
+    #[serde(default)]
     pub close: String,
 }
+
+/* Order Price */
+// A price as AddOrder accepts it: either absolute, or an offset from the last
+// traded price written as `+5`, `-5` or `+5%`. A `#` offset leaves the
+// direction to the order side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderPrice {
+    Fixed(Decimal),
+    Offset {
+        sign: OffsetSign,
+        amount: Decimal,
+        percent: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OffsetSign {
+    Plus,
+    Minus,
+    // `#`
+    Auto,
+}
+
+impl From<Decimal> for OrderPrice {
+    fn from(price: Decimal) -> Self {
+        OrderPrice::Fixed(price)
+    }
+}
+
+impl FromStr for OrderPrice {
+    type Err = rust_decimal::Error;
+
+    fn from_str(price: &str) -> Result<Self, Self::Err> {
+        let (sign, rest) = match price.chars().next() {
+            Some('+') => (Some(OffsetSign::Plus), &price[1..]),
+            Some('-') => (Some(OffsetSign::Minus), &price[1..]),
+            Some('#') => (Some(OffsetSign::Auto), &price[1..]),
+            _ => (None, price),
+        };
+        let (amount, percent) = match rest.strip_suffix('%') {
+            Some(amount) => (amount, true),
+            None => (rest, false),
+        };
+        let amount = Decimal::from_str(amount)?;
+        match (sign, percent) {
+            (None, false) => Ok(OrderPrice::Fixed(amount)),
+            (None, true) => Err(rust_decimal::Error::ErrorString(format!("Percent price {} needs a +, - or # prefix.", price))),
+            (Some(sign), _) => Ok(OrderPrice::Offset {
+                sign,
+                amount,
+                percent,
+            }),
+        }
+    }
+}
+
+impl fmt::Display for OrderPrice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrderPrice::Fixed(price) => write!(f, "{}", price),
+            OrderPrice::Offset { sign, amount, percent } => {
+                let sign = match sign {
+                    OffsetSign::Plus => "+",
+                    OffsetSign::Minus => "-",
+                    OffsetSign::Auto => "#",
+                };
+                let percent = match percent {
+                    true => "%",
+                    false => "",
+                };
+                write!(f, "{}{}{}", sign, amount, percent)
+            },
+        }
+    }
+}
+
+/* Order Validation */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    // Refuse prices off the tick size and volumes past the lot decimals.
+    Reject,
+    // Round prices to the nearest tick and volumes down to the lot decimals.
+    Round,
+}
+
+// Percent offsets are left alone; absolute prices and offsets have to be
+// whole ticks.
+fn fit_price(price: OrderPrice, tick: Decimal, rounding: Rounding, name: &str, pair: &str) -> Result<OrderPrice, ExchangeError> {
+    let amount = match price {
+        OrderPrice::Fixed(amount) => amount,
+        OrderPrice::Offset { amount, percent: false, .. } => amount,
+        OrderPrice::Offset { percent: true, .. } => return Ok(price),
+    };
+    if (amount % tick).is_zero() {
+        return Ok(price);
+    }
+    let rounded = match rounding {
+        Rounding::Reject => return Err(ExchangeError::InvalidOrder(format!(
+            "{} {} is not a multiple of the {} tick size for {}.", name, amount, tick, pair,
+        ))),
+        Rounding::Round => (amount / tick).round() * tick,
+    };
+    match price {
+        OrderPrice::Fixed(_) => Ok(OrderPrice::Fixed(rounded)),
+        OrderPrice::Offset { sign, percent, .. } => Ok(OrderPrice::Offset {
+            sign,
+            amount: rounded,
+            percent,
+        }),
+    }
+}
+
+// Leverage is written `2:1` or `2`; `none` trades without leverage.
+fn check_leverage(leverage: &str, side: Option<Side>, pair: &TradingPair) -> Result<(), ExchangeError> {
+    if leverage == "none" {
+        return Ok(());
+    }
+    let ratio: i64 = match leverage.split(':').next().map(str::parse) {
+        Some(Ok(ratio)) => ratio,
+        _ => return Err(ExchangeError::InvalidOrder(format!("Invalid leverage {}.", leverage))),
+    };
+    let (allowed, side) = match side {
+        Some(Side::Buy) => (&pair.leverage_buy, "buy"),
+        Some(Side::Sell) => (&pair.leverage_sell, "sell"),
+        None => return Ok(()),
+    };
+    if allowed.contains(&ratio) {
+        return Ok(());
+    }
+    match allowed.is_empty() {
+        true => Err(ExchangeError::InvalidOrder(format!("{} does not offer leverage to {} orders.", pair.altname, side))),
+        false => {
+            let allowed: Vec<String> = allowed.iter().map(|ratio| ratio.to_string()).collect();
+            Err(ExchangeError::InvalidOrder(format!(
+                "Leverage {} is not offered to {} orders on {} (offered: {}).", leverage, side, pair.altname, allowed.join(", "),
+            )))
+        },
+    }
+}
+
+/* Order Placement */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderTime {
+    // Sent as `+<seconds>`, counted from the exchange's own clock.
+    After(Duration),
+    // A local clock reading, converted to the exchange's clock before sending.
+    At(SystemTime),
+}
+
+impl OrderTime {
+    pub fn to_param(&self, clock_offset: &ClockOffset) -> String {
+        match self {
+            OrderTime::After(delay) => format!("+{}", delay.as_secs()),
+            OrderTime::At(time) => {
+                let server_time = clock_offset.to_server(*time);
+                let since_epoch = server_time.duration_since(UNIX_EPOCH).expect("Error creating since_epoch");
+                since_epoch.as_secs().to_string()
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NewOrder {
+    pub pair: String,
+    pub r#type: Option<Side>,
+    pub ordertype: Option<OrderType>,
+    pub volume: Decimal,
+    pub price: Option<OrderPrice>,
+    pub price2: Option<OrderPrice>,
+    pub leverage: Option<String>,
+    pub oflags: OrderFlags,
+    pub starttm: Option<OrderTime>,
+    pub expiretm: Option<OrderTime>,
+    pub userref: Option<i32>,
+    // Client order id, used to find the order again if the response is lost.
+    pub cl_ord_id: Option<String>,
+    pub validate: bool,
+}
+
+impl NewOrder {
+    pub fn new(pair: &str, r#type: Side, ordertype: OrderType, volume: Decimal) -> Self {
+        Self {
+            pair: pair.to_string(),
+            r#type: Some(r#type),
+            ordertype: Some(ordertype),
+            volume,
+            ..Default::default()
+        }
+    }
+
+    pub fn price(mut self, price: impl Into<OrderPrice>) -> Self {
+        self.price = Some(price.into());
+        self
+    }
+
+    pub fn price2(mut self, price2: impl Into<OrderPrice>) -> Self {
+        self.price2 = Some(price2.into());
+        self
+    }
+
+    pub fn leverage(mut self, leverage: &str) -> Self {
+        self.leverage = Some(leverage.to_string());
+        self
+    }
+
+    pub fn oflag(mut self, oflag: OrderFlag) -> Self {
+        self.oflags.insert(oflag);
+        self
+    }
+
+    pub fn starttm(mut self, starttm: OrderTime) -> Self {
+        self.starttm = Some(starttm);
+        self
+    }
+
+    pub fn expiretm(mut self, expiretm: OrderTime) -> Self {
+        self.expiretm = Some(expiretm);
+        self
+    }
+
+    pub fn userref(mut self, userref: i32) -> Self {
+        self.userref = Some(userref);
+        self
+    }
+
+    pub fn cl_ord_id(mut self, cl_ord_id: &str) -> Self {
+        self.cl_ord_id = Some(cl_ord_id.to_string());
+        self
+    }
+
+    pub fn validate_only(mut self) -> Self {
+        self.validate = true;
+        self
+    }
+
+    // Checks the order against the pair's trading rules so it is refused
+    // here rather than by the exchange. Prices off the tick size and volumes
+    // past the lot decimals are rounded or rejected depending on `rounding`.
+    // Orders under the minimum volume or cost, and leverage the pair does not
+    // offer, are always rejected. The cost is only checked for fixed prices.
+    pub fn validated(mut self, pair: &TradingPair, rounding: Rounding) -> Result<Self, ExchangeError> {
+        let tick = pair.price_increment();
+        if let Some(price) = self.price {
+            self.price = Some(fit_price(price, tick, rounding, "Price", &pair.altname)?);
+        }
+        if let Some(price2) = self.price2 {
+            self.price2 = Some(fit_price(price2, tick, rounding, "Secondary price", &pair.altname)?);
+        }
+
+        // With `viqc` the volume is in the quote currency, so it is the cost.
+        let cost = match self.oflags.contains(&OrderFlag::Viqc) {
+            true => Some(self.volume),
+            false => {
+                let lot_decimals = pair.lot_decimals as u32;
+                let volume = self.volume.round_dp_with_strategy(lot_decimals, RoundingStrategy::ToZero);
+                if volume != self.volume {
+                    match rounding {
+                        Rounding::Reject => return Err(ExchangeError::InvalidOrder(format!(
+                            "Volume {} has more than the {} decimals allowed for {}.", self.volume, lot_decimals, pair.altname,
+                        ))),
+                        Rounding::Round => self.volume = volume,
+                    }
+                }
+                if self.volume < pair.ordermin {
+                    return Err(ExchangeError::InvalidOrder(format!(
+                        "Volume {} is below the minimum of {} for {}.", self.volume, pair.ordermin, pair.altname,
+                    )));
+                }
+                // Market orders have no price and offset prices follow the
+                // market when the exchange places them, so their cost is not
+                // known here. The exchange still enforces costmin for them.
+                match self.price {
+                    Some(OrderPrice::Fixed(price)) => Some(self.volume * price),
+                    _ => None,
+                }
+            },
+        };
+        if let (Some(cost), Some(costmin)) = (cost, pair.costmin) {
+            if cost < costmin {
+                return Err(ExchangeError::InvalidOrder(format!(
+                    "Order value {} is below the minimum of {} for {}.", cost, costmin, pair.altname,
+                )));
+            }
+        }
+
+        if let Some(leverage) = &self.leverage {
+            check_leverage(leverage, self.r#type, pair)?;
+        }
+        Ok(self)
+    }
+
+    // Absolute times depend on the clock offset being known.
+    pub fn has_absolute_time(&self) -> bool {
+        [self.starttm, self.expiretm].iter().any(|t| matches!(t, Some(OrderTime::At(_))))
+    }
+
+    pub fn to_payload(&self, clock_offset: &ClockOffset) -> Payload {
+        let mut payload = Payload::new();
+        payload.insert("pair", &self.pair);
+        if let Some(side) = self.r#type {
+            payload.insert("type", side);
+        }
+        if let Some(ordertype) = self.ordertype {
+            payload.insert("ordertype", ordertype);
+        }
+        payload.insert("volume", self.volume);
+        if let Some(price) = &self.price {
+            payload.insert("price", price);
+        }
+        if let Some(price2) = &self.price2 {
+            payload.insert("price2", price2);
+        }
+        if let Some(leverage) = &self.leverage {
+            payload.insert("leverage", leverage);
+        }
+        if !self.oflags.is_empty() {
+            payload.insert("oflags", &self.oflags);
+        }
+        if let Some(starttm) = &self.starttm {
+            payload.insert("starttm", starttm.to_param(clock_offset));
+        }
+        if let Some(expiretm) = &self.expiretm {
+            payload.insert("expiretm", expiretm.to_param(clock_offset));
+        }
+        if let Some(userref) = self.userref {
+            payload.insert("userref", userref);
+        }
+        if let Some(cl_ord_id) = &self.cl_ord_id {
+            payload.insert("cl_ord_id", cl_ord_id);
+        }
+        if self.validate {
+            payload.insert("validate", true);
+        }
+        payload
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddOrderDescription {
+    pub order: String,
+    pub close: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddOrderResult {
+    pub descr: AddOrderDescription,
+    #[serde(default)]
+    pub txid: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CancelOrderResult {
+    pub count: i64,
+    #[serde(default)]
+    pub pending: Option<bool>,
+}
+
+/* Order Lookup */
+// Just enough of an OpenOrders or ClosedOrders result to find an order by
+// its client order id or userref.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct OrderLookup {
+    #[serde(default)]
+    pub open: HashMap<String, OrderSummary>,
+    #[serde(default)]
+    pub closed: HashMap<String, OrderSummary>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrderSummary {
+    #[serde(default)]
+    pub cl_ord_id: Option<String>,
+    #[serde(default)]
+    pub userref: Option<i64>,
+    #[serde(default)]
+    pub opentm: Option<Timestamp>,
+    #[serde(default)]
+    pub status: Option<OrderStatus>,
+    pub descr: OrderSummaryDescription,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrderSummaryDescription {
+    pub order: String,
+    #[serde(default)]
+    pub close: Option<String>,
+}
+
+impl OrderLookup {
+    pub fn find(self, cl_ord_id: &str) -> Option<(String, OrderSummary)> {
+        self.open
+            .into_iter()
+            .chain(self.closed)
+            .find(|(_, order)| order.cl_ord_id.as_deref() == Some(cl_ord_id))
+    }
+
+    // Orders with `userref` opened at or after `since`. Userrefs need not be
+    // unique, so older orders that share it are left out.
+    pub fn find_userref(self, userref: i32, since: Timestamp) -> Vec<(String, OrderSummary)> {
+        self.open
+            .into_iter()
+            .chain(self.closed)
+            .filter(|(_, order)| order.userref == Some(i64::from(userref)) && order.opentm.is_some_and(|opened| opened >= since))
+            .collect()
+    }
+}
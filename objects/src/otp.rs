@@ -0,0 +1,127 @@
+use crate::{
+    errors::ExchangeError,
+    secrets::SecretString,
+};
+
+#[cfg(feature = "otp")]
+use crate::secrets::SecretKey;
+
+#[cfg(feature = "otp")]
+use base32::Alphabet;
+
+#[cfg(feature = "otp")]
+use hmac::{
+    Hmac,
+    Mac,
+    NewMac,
+};
+
+#[cfg(feature = "otp")]
+use sha1::Sha1;
+
+use std::{
+    fmt,
+    time::SystemTime,
+};
+
+#[cfg(feature = "otp")]
+use std::time::{
+    Duration,
+    UNIX_EPOCH,
+};
+
+
+
+/* OTP Provider */
+pub trait OtpProvider: fmt::Debug + Send + Sync {
+    // The one-time password to send as `otp` with a request made at `now`
+    // (on the exchange's clock).
+    fn code(&self, now: SystemTime) -> Result<String, ExchangeError>;
+}
+
+/* Static Password */
+#[derive(Debug, Clone)]
+pub struct StaticOtp {
+    password: SecretString,
+}
+
+impl StaticOtp {
+    pub fn new(password: &str) -> Self {
+        Self {
+            password: SecretString::new(password),
+        }
+    }
+}
+
+impl OtpProvider for StaticOtp {
+    fn code(&self, _now: SystemTime) -> Result<String, ExchangeError> {
+        Ok(self.password.expose().to_string())
+    }
+}
+
+/* Time-Based Password (RFC 6238) */
+#[cfg(feature = "otp")]
+#[derive(Debug, Clone)]
+pub struct Totp {
+    secret: SecretKey,
+    digits: u32,
+    step: Duration,
+}
+
+#[cfg(feature = "otp")]
+impl Totp {
+    // Codes have 6 to 8 digits and the step is at least one second.
+    pub fn new(secret: SecretKey, digits: u32, step: Duration) -> Result<Self, ExchangeError> {
+        if step.as_secs() == 0 {
+            return Err(ExchangeError::Otp(format!("TOTP step of {:?} is shorter than one second.", step)));
+        }
+        Self {
+            secret,
+            digits: 6,
+            step,
+        }
+        .digits(digits)
+    }
+
+    // Authenticator-app keys: base32, case-insensitive, spaces and padding
+    // ignored. Codes are 6 digits over a 30 second step.
+    pub fn from_base32(secret: &str) -> Result<Self, ExchangeError> {
+        let normalized: String = secret
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '=')
+            .collect::<String>()
+            .to_uppercase();
+        match base32::decode(Alphabet::RFC4648 { padding: false }, &normalized) {
+            Some(bytes) if !bytes.is_empty() => Self::new(SecretKey::new(bytes), 6, Duration::from_secs(30)),
+            _ => Err(ExchangeError::Otp("Invalid base32 TOTP secret.".to_string())),
+        }
+    }
+
+    pub fn digits(mut self, digits: u32) -> Result<Self, ExchangeError> {
+        if !(6..=8).contains(&digits) {
+            return Err(ExchangeError::Otp(format!("TOTP codes have 6 to 8 digits, not {}.", digits)));
+        }
+        self.digits = digits;
+        Ok(self)
+    }
+
+    pub fn code_at(&self, time: SystemTime) -> String {
+        let since_epoch = time.duration_since(UNIX_EPOCH).expect("Error creating since_epoch");
+        let counter = since_epoch.as_secs() / self.step.as_secs();
+
+        let mut hmac_1: Hmac<Sha1> = Hmac::<Sha1>::new_varkey(self.secret.expose()).expect("Error creating Hmac<Sha1>.");
+        hmac_1.update(&counter.to_be_bytes());
+        let hash = hmac_1.finalize().into_bytes();
+
+        let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+        let truncated = u32::from_be_bytes([hash[offset], hash[offset + 1], hash[offset + 2], hash[offset + 3]]) & 0x7fff_ffff;
+        format!("{:0width$}", truncated % 10u32.pow(self.digits), width = self.digits as usize)
+    }
+}
+
+#[cfg(feature = "otp")]
+impl OtpProvider for Totp {
+    fn code(&self, now: SystemTime) -> Result<String, ExchangeError> {
+        Ok(self.code_at(now))
+    }
+}
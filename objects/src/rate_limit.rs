@@ -0,0 +1,267 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use std::{
+    collections::HashMap,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+
+
+/* Account Tier */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountTier {
+    Starter,
+    Intermediate,
+    Pro,
+}
+
+impl AccountTier {
+    // (maximum, decay per second) of the per-key API call counter.
+    pub fn api_counter(&self) -> (f64, f64) {
+        match self {
+            AccountTier::Starter => (15.0, 0.33),
+            AccountTier::Intermediate => (20.0, 0.5),
+            AccountTier::Pro => (20.0, 1.0),
+        }
+    }
+
+    // (threshold, decay per second) of the per-pair matching engine order-rate counter.
+    pub fn order_rate(&self) -> (f64, f64) {
+        match self {
+            AccountTier::Starter => (60.0, 1.0),
+            AccountTier::Intermediate => (125.0, 2.34),
+            AccountTier::Pro => (180.0, 3.75),
+        }
+    }
+}
+
+/* Limit Mode */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RateLimitMode {
+    // Wait until the call fits under the limit.
+    Queue,
+    // Fail the call with `ExchangeError::RateLimited`.
+    Reject,
+}
+
+/* Endpoint Cost */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EndpointCost {
+    pub api_counter: f64,
+    pub order_rate: f64,
+}
+
+impl EndpointCost {
+    // Public endpoints are limited per IP and do not touch the key's counters.
+    // Ledger and trade history calls cost 2, other private calls cost 1.
+    // Order placement counts against the pair's order-rate counter instead.
+    pub fn for_endpoint(href: &str) -> Self {
+        let name = href.rsplit('/').next().unwrap_or("");
+        let (api_counter, order_rate) = match href.contains("private") {
+            false => (0.0, 0.0),
+            true => match name {
+                "Ledgers" | "QueryLedgers" | "TradesHistory" | "QueryTrades" => (2.0, 0.0),
+                "AddOrder" | "EditOrder" => (0.0, 1.0),
+                "CancelOrder" => (0.0, 0.0),
+                _ => (1.0, 0.0),
+            },
+        };
+        Self {
+            api_counter,
+            order_rate,
+        }
+    }
+}
+
+// Penalty added to the pair's order-rate counter when an order is cancelled,
+// based on how long it rested on the book. Unknown ages use the maximum.
+pub fn cancel_penalty(age: Option<Duration>) -> f64 {
+    match age.map(|a| a.as_secs()) {
+        Some(s) if s >= 300 => 0.0,
+        Some(s) if s >= 90 => 1.0,
+        Some(s) if s >= 45 => 2.0,
+        Some(s) if s >= 15 => 4.0,
+        Some(s) if s >= 10 => 5.0,
+        Some(s) if s >= 5 => 6.0,
+        _ => 8.0,
+    }
+}
+
+/* Decaying Counter */
+#[derive(Debug, Clone)]
+pub struct DecayingCounter {
+    value: f64,
+    max: f64,
+    decay_per_sec: f64,
+    updated: Instant,
+}
+
+impl DecayingCounter {
+    pub fn new(max: f64, decay_per_sec: f64) -> Self {
+        Self {
+            value: 0.0,
+            max,
+            decay_per_sec,
+            updated: Instant::now(),
+        }
+    }
+
+    fn decay(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.value = (self.value - elapsed * self.decay_per_sec).max(0.0);
+        self.updated = now;
+    }
+
+    pub fn value(&mut self) -> f64 {
+        self.decay(Instant::now());
+        self.value
+    }
+
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+
+    pub fn headroom(&mut self) -> f64 {
+        self.max - self.value()
+    }
+
+    // How long until `cost` fits under the maximum.
+    fn wait_for(&mut self, cost: f64, now: Instant) -> Duration {
+        self.decay(now);
+        let excess = self.value + cost - self.max;
+        match excess > 0.0 {
+            true => Duration::from_secs_f64(excess / self.decay_per_sec),
+            false => Duration::ZERO,
+        }
+    }
+
+    fn add(&mut self, cost: f64) {
+        self.value += cost;
+    }
+}
+
+/* Headroom Report */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Headroom {
+    pub counter: f64,
+    pub max: f64,
+    pub remaining: f64,
+}
+
+// An order accepted by the exchange, remembered until cancelling it would no
+// longer be penalised.
+#[derive(Debug, Clone)]
+struct PlacedOrder {
+    ids: Vec<String>,
+    pair: String,
+    placed: Instant,
+}
+
+/* Rate Limiter */
+// Order-rate counters are keyed by the pair name they are given; pass the
+// canonical id, e.g. `XXBTZUSD`, so every alias of a pair shares one counter.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    pub tier: AccountTier,
+    pub mode: RateLimitMode,
+    api_counter: DecayingCounter,
+    order_counters: HashMap<String, DecayingCounter>,
+    placed: Vec<PlacedOrder>,
+}
+
+impl RateLimiter {
+    pub fn new(tier: AccountTier, mode: RateLimitMode) -> Self {
+        let (max, decay) = tier.api_counter();
+        Self {
+            tier,
+            mode,
+            api_counter: DecayingCounter::new(max, decay),
+            order_counters: HashMap::new(),
+            placed: Vec::new(),
+        }
+    }
+
+    fn order_counter(&mut self, pair: &str) -> &mut DecayingCounter {
+        let (max, decay) = self.tier.order_rate();
+        self.order_counters
+            .entry(pair.to_string())
+            .or_insert_with(|| DecayingCounter::new(max, decay))
+    }
+
+    // Records the call if it fits under every limit it touches, otherwise
+    // returns how long the caller has to wait before trying again.
+    pub fn reserve(&mut self, href: &str, pair: Option<&str>) -> Result<(), Duration> {
+        let now = Instant::now();
+        let cost = EndpointCost::for_endpoint(href);
+        let mut wait = self.api_counter.wait_for(cost.api_counter, now);
+        if let (Some(pair), true) = (pair, cost.order_rate > 0.0) {
+            wait = wait.max(self.order_counter(pair).wait_for(cost.order_rate, now));
+        }
+        if !wait.is_zero() {
+            return Err(wait);
+        }
+        self.api_counter.add(cost.api_counter);
+        if let (Some(pair), true) = (pair, cost.order_rate > 0.0) {
+            self.order_counter(pair).add(cost.order_rate);
+        }
+        Ok(())
+    }
+
+    pub fn record_cancel(&mut self, pair: &str, age: Option<Duration>) {
+        self.order_counter(pair).add(cancel_penalty(age));
+    }
+
+    // Remembers an accepted order, so that cancelling it by any of `ids`
+    // charges the penalty for its age to `pair`.
+    pub fn record_order(&mut self, ids: Vec<String>, pair: &str) {
+        let now = Instant::now();
+        self.placed.retain(|order| cancel_penalty(Some(now.saturating_duration_since(order.placed))) > 0.0);
+        self.placed.push(PlacedOrder {
+            ids,
+            pair: pair.to_string(),
+            placed: now,
+        });
+    }
+
+    // Charges the cancel penalty of an order passed to `record_order`. Returns
+    // false for orders it does not know, e.g. ones placed by another process,
+    // as their pair is unknown.
+    pub fn record_cancel_of(&mut self, order_id: &str) -> bool {
+        let index = match self.placed.iter().position(|order| order.ids.iter().any(|id| id == order_id)) {
+            Some(index) => index,
+            None => return false,
+        };
+        let order = self.placed.swap_remove(index);
+        self.record_cancel(&order.pair, Some(order.placed.elapsed()));
+        true
+    }
+
+    pub fn headroom(&mut self) -> Headroom {
+        let counter = self.api_counter.value();
+        let max = self.api_counter.max();
+        Headroom {
+            counter,
+            max,
+            remaining: max - counter,
+        }
+    }
+
+    pub fn order_headroom(&mut self, pair: &str) -> Headroom {
+        let order_counter = self.order_counter(pair);
+        let counter = order_counter.value();
+        let max = order_counter.max();
+        Headroom {
+            counter,
+            max,
+            remaining: max - counter,
+        }
+    }
+}
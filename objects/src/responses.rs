@@ -1,11 +1,4 @@
-use crate::{
-    orders::OpenOrders,
-    system_server::{
-        ServerTime,
-        SystemStatus,
-    },
-    trades::TradingPairs,
-};
+use crate::errors::ExchangeError;
 
 use serde::{
     Deserialize,
@@ -14,24 +7,21 @@ use serde::{
 
 
 
+/* Response Object */
 #[derive(Debug, Serialize, Deserialize)]
-pub struct TradingPairResponse {
+pub struct APIResponse<T> {
     pub error: Vec<String>,
-    pub result: TradingPairs,
+    pub result: Option<T>,
 }
 
-/* Result Enum */
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(untagged)]
-pub enum ResponseResult {
-    OpenOrdersResponse(OpenOrders),
-    ServerTimeResponse(ServerTime),
-    SystemStatusResponse(SystemStatus),
+impl<T> APIResponse<T> {
+    pub fn into_result(self) -> Result<T, ExchangeError> {
+        if !self.error.is_empty() {
+            return Err(ExchangeError::Api(self.error));
+        }
+        match self.result {
+            Some(result) => Ok(result),
+            None => Err(ExchangeError::MissingResult),
+        }
+    }
 }
-
-/* Response Object */
-#[derive(Debug, Serialize, Deserialize)]
-pub struct APIResponse {
-    pub error: Vec<String>,
-    pub result: ResponseResult,
-}
\ No newline at end of file
@@ -0,0 +1,103 @@
+use crate::errors::ExchangeError;
+
+use std::time::Duration;
+
+
+
+/* Error Class */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    // No response: connection failures and timeouts.
+    Network,
+    // HTTP 5xx.
+    ServerError,
+    // HTTP 429 or `EAPI:Rate limit exceeded`.
+    RateLimited,
+    // `EService:Unavailable` or `EService:Busy`.
+    Unavailable,
+    // `EAPI:Invalid nonce`.
+    InvalidNonce,
+}
+
+impl ErrorClass {
+    pub fn of(err: &ExchangeError) -> Option<Self> {
+        match err {
+            ExchangeError::Request(_) => Some(ErrorClass::Network),
+            ExchangeError::Status(status) if status.is_server_error() => Some(ErrorClass::ServerError),
+            ExchangeError::Status(status) if status.as_u16() == 429 => Some(ErrorClass::RateLimited),
+            ExchangeError::Api(errors) => errors.iter().find_map(|e| match e {
+                e if e.starts_with("EAPI:Rate limit exceeded") => Some(ErrorClass::RateLimited),
+                e if e.starts_with("EService:Unavailable") || e.starts_with("EService:Busy") => Some(ErrorClass::Unavailable),
+                e if e.starts_with("EAPI:Invalid nonce") => Some(ErrorClass::InvalidNonce),
+                _ => None,
+            }),
+            _ => None,
+        }
+    }
+
+    // The request may have been carried out even though no result came back.
+    // The other classes are rejections, so the call did not take effect.
+    pub fn is_ambiguous(&self) -> bool {
+        matches!(self, ErrorClass::Network | ErrorClass::ServerError)
+    }
+}
+
+// Calls that change account state. Repeating one after an ambiguous failure
+// could apply it twice, so they are never retried blindly.
+pub const NON_IDEMPOTENT_ENDPOINTS: [&str; 10] = [
+    "AddOrder",
+    "AddOrderBatch",
+    "EditOrder",
+    "CancelOrder",
+    "CancelAll",
+    "CancelAllOrdersAfter",
+    "CancelOrderBatch",
+    "Withdraw",
+    "WalletTransfer",
+    "WithdrawCancel",
+];
+
+pub fn is_idempotent(endpoint: &str) -> bool {
+    !NON_IDEMPOTENT_ENDPOINTS.contains(&endpoint)
+}
+
+/* Retry Policy */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    // Total attempts, including the first.
+    pub max_attempts: u32,
+    // Delay before the second attempt, doubled for each one after.
+    pub backoff: Duration,
+    pub max_backoff: Duration,
+    pub retry_on: Vec<ErrorClass>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(5),
+            retry_on: vec![
+                ErrorClass::Network,
+                ErrorClass::ServerError,
+                ErrorClass::Unavailable,
+                ErrorClass::InvalidNonce,
+            ],
+        }
+    }
+}
+
+impl RetryPolicy {
+    // Whether to make another attempt after attempt number `attempt`
+    // (counting from 1) failed with `err`.
+    pub fn should_retry(&self, err: &ExchangeError, attempt: u32) -> bool {
+        attempt < self.max_attempts && ErrorClass::of(err).is_some_and(|class| self.retry_on.contains(&class))
+    }
+
+    // Delay before attempt `attempt + 1`.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+}
@@ -0,0 +1,297 @@
+use crate::{
+    errors::ExchangeError,
+    secrets::SecretKey,
+};
+
+use base64::{
+    decode,
+    encode,
+};
+
+use hmac::{
+    Hmac,
+    Mac,
+    NewMac,
+};
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use sha2::{
+    Digest,
+    Sha256,
+    Sha512,
+};
+
+use std::fmt;
+
+#[cfg(unix)]
+use std::{
+    collections::HashSet,
+    fs::{
+        self,
+        DirBuilder,
+    },
+    io::{
+        BufRead,
+        BufReader,
+        Write,
+    },
+    os::unix::{
+        fs::{
+            DirBuilderExt,
+            PermissionsExt,
+        },
+        net::{
+            UnixListener,
+            UnixStream,
+        },
+    },
+    path::{
+        Path,
+        PathBuf,
+    },
+    process,
+    sync::Arc,
+    thread,
+    time::Duration,
+};
+
+
+
+/* Signer */
+// Signers may block, e.g. on a round trip to a daemon; `BaseExchange` runs
+// them on tokio's blocking pool so async workers are not held up.
+pub trait Signer: fmt::Debug + Send + Sync {
+    // The `API-Sign` value for a private request to `path` (e.g.
+    // `/0/private/AddOrder`) with the given nonce and encoded body.
+    fn sign(&self, path: &str, nonce: &str, encoded_payload: &str) -> Result<String, ExchangeError>;
+}
+
+/* In-Process Signer */
+#[derive(Debug, Clone)]
+pub struct HmacSigner {
+    secret: SecretKey,
+}
+
+impl HmacSigner {
+    pub fn new(secret: SecretKey) -> Self {
+        Self {
+            secret,
+        }
+    }
+}
+
+impl Signer for HmacSigner {
+    fn sign(&self, path: &str, nonce: &str, encoded_payload: &str) -> Result<String, ExchangeError> {
+        hmac_sign(&self.secret, path, nonce, encoded_payload)
+    }
+}
+
+// API-Sign = base64(HMAC-SHA512(path, SHA256(nonce + encoded_payload)))
+pub fn hmac_sign(secret: &SecretKey, path: &str, nonce: &str, encoded_payload: &str) -> Result<String, ExchangeError> {
+    Ok(encode(request_mac(secret, path, nonce, encoded_payload)?.finalize().into_bytes()))
+}
+
+// Checks an `API-Sign` value the way the exchange does, so mock exchanges and
+// gateways can authenticate requests from this client. `body` is the raw
+// request body, which must include the same `nonce`. The comparison is
+// constant-time; a malformed signature never verifies.
+pub fn verify_signature(secret: &SecretKey, path: &str, nonce: &str, body: &str, sign: &str) -> bool {
+    let sign = match decode(sign) {
+        Ok(sign) => sign,
+        Err(_) => return false,
+    };
+    match request_mac(secret, path, nonce, body) {
+        Ok(mac) => mac.verify(&sign).is_ok(),
+        Err(_) => false,
+    }
+}
+
+fn request_mac(secret: &SecretKey, path: &str, nonce: &str, encoded_payload: &str) -> Result<Hmac<Sha512>, ExchangeError> {
+    let mut sha_digest: Sha256 = Sha256::default();
+    sha_digest.update(nonce.as_bytes());
+    sha_digest.update(encoded_payload.as_bytes());
+    let hashed_payload: Vec<u8> = sha_digest.finalize().to_vec();
+
+    let mut hmac_512: Hmac<Sha512> = Hmac::<Sha512>::new_varkey(secret.expose())
+        .map_err(|e| ExchangeError::Signing(e.to_string()))?;
+    hmac_512.update(path.as_bytes());
+    hmac_512.update(&hashed_payload);
+    Ok(hmac_512)
+}
+
+/* Daemon Protocol */
+// One JSON object per line in each direction.
+#[derive(Debug, Serialize, Deserialize)]
+struct SignRequest {
+    path: String,
+    nonce: String,
+    body: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SignResponse {
+    #[serde(default)]
+    sign: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/* Signing Daemon */
+// Holds the secret in its own process and signs requests for an allowlist of
+// endpoints, so trading processes never see the secret itself.
+#[cfg(unix)]
+#[derive(Debug, Clone)]
+pub struct SigningDaemon {
+    signer: Arc<dyn Signer>,
+    // Signed paths, e.g. `/0/private/AddOrder`.
+    allowed: HashSet<String>,
+}
+
+#[cfg(unix)]
+impl SigningDaemon {
+    pub fn new(signer: Arc<dyn Signer>, allowed: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            signer,
+            allowed: allowed.into_iter().collect(),
+        }
+    }
+
+    // Binds the socket, replacing a stale socket file left by a previous run.
+    // The socket is only accessible to the owning user: it is bound inside a
+    // directory only the owner can enter, made 0600 there, and then moved
+    // into place, so it is never reachable with looser permissions.
+    pub fn bind(path: impl AsRef<Path>) -> Result<UnixListener, ExchangeError> {
+        let path = path.as_ref();
+        if path.exists() {
+            fs::remove_file(path).map_err(signing_err)?;
+        }
+        let mut private_dir = path.as_os_str().to_owned();
+        private_dir.push(format!(".bind-{}", process::id()));
+        let private_dir = PathBuf::from(private_dir);
+        if private_dir.exists() {
+            fs::remove_dir_all(&private_dir).map_err(signing_err)?;
+        }
+        DirBuilder::new().mode(0o700).create(&private_dir).map_err(signing_err)?;
+
+        let bound = private_dir.join("socket");
+        let result = UnixListener::bind(&bound).and_then(|listener| {
+            fs::set_permissions(&bound, fs::Permissions::from_mode(0o600))?;
+            fs::rename(&bound, path)?;
+            Ok(listener)
+        });
+        let _ = fs::remove_dir_all(&private_dir);
+        result.map_err(signing_err)
+    }
+
+    // Serves connections until the listener fails, one thread per connection.
+    pub fn serve(&self, listener: UnixListener) -> Result<(), ExchangeError> {
+        for stream in listener.incoming() {
+            let stream = stream.map_err(signing_err)?;
+            let daemon = self.clone();
+            thread::spawn(move || {
+                if let Err(e) = daemon.handle(stream) {
+                    log::warn!("Signing connection failed: {}", e);
+                }
+            });
+        }
+        Ok(())
+    }
+
+    fn handle(&self, stream: UnixStream) -> Result<(), ExchangeError> {
+        let mut writer = stream.try_clone().map_err(signing_err)?;
+        for line in BufReader::new(stream).lines() {
+            let line = line.map_err(signing_err)?;
+            let response = match serde_json::from_str::<SignRequest>(&line) {
+                Ok(request) => self.respond(&request),
+                Err(e) => SignResponse {
+                    error: Some(format!("Invalid request: {}", e)),
+                    ..SignResponse::default()
+                },
+            };
+            let mut out = serde_json::to_string(&response).map_err(signing_err)?;
+            out.push('\n');
+            writer.write_all(out.as_bytes()).map_err(signing_err)?;
+        }
+        Ok(())
+    }
+
+    fn respond(&self, request: &SignRequest) -> SignResponse {
+        if !self.allowed.contains(&request.path) {
+            log::warn!("Refused to sign {}.", request.path);
+            return SignResponse {
+                error: Some(format!("Endpoint {} is not approved for signing.", request.path)),
+                ..SignResponse::default()
+            };
+        }
+        match self.signer.sign(&request.path, &request.nonce, &request.body) {
+            Ok(sign) => SignResponse {
+                sign: Some(sign),
+                error: None,
+            },
+            Err(e) => SignResponse {
+                sign: None,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+}
+
+/* Daemon Client */
+#[cfg(unix)]
+#[derive(Debug, Clone)]
+pub struct DaemonSigner {
+    socket: PathBuf,
+    timeout: Duration,
+}
+
+#[cfg(unix)]
+impl DaemonSigner {
+    pub fn new(socket: impl AsRef<Path>) -> Self {
+        Self {
+            socket: socket.as_ref().to_path_buf(),
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+#[cfg(unix)]
+impl Signer for DaemonSigner {
+    fn sign(&self, path: &str, nonce: &str, encoded_payload: &str) -> Result<String, ExchangeError> {
+        let mut stream = UnixStream::connect(&self.socket)
+            .map_err(|e| ExchangeError::Signing(format!("{}: {}", self.socket.display(), e)))?;
+        stream.set_read_timeout(Some(self.timeout)).map_err(signing_err)?;
+        stream.set_write_timeout(Some(self.timeout)).map_err(signing_err)?;
+
+        let request = SignRequest {
+            path: path.to_string(),
+            nonce: nonce.to_string(),
+            body: encoded_payload.to_string(),
+        };
+        let mut out = serde_json::to_string(&request).map_err(signing_err)?;
+        out.push('\n');
+        stream.write_all(out.as_bytes()).map_err(signing_err)?;
+
+        let mut line = String::new();
+        BufReader::new(stream).read_line(&mut line).map_err(signing_err)?;
+        let response: SignResponse = serde_json::from_str(&line).map_err(signing_err)?;
+        match (response.sign, response.error) {
+            (Some(sign), None) => Ok(sign),
+            (_, Some(error)) => Err(ExchangeError::Signing(error)),
+            (None, None) => Err(ExchangeError::Signing("Empty response from signing daemon.".to_string())),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn signing_err(err: impl fmt::Display) -> ExchangeError {
+    ExchangeError::Signing(err.to_string())
+}
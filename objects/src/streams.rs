@@ -0,0 +1,43 @@
+use crate::{
+    errors::ExchangeError,
+    exchanges::BaseExchange,
+    orders::OpenOrders,
+};
+
+use futures_util::stream::{
+    self,
+    Stream,
+};
+
+use std::{
+    future::Future,
+    time::Duration,
+};
+
+use tokio::time::sleep;
+
+
+
+/* Polling */
+// Calls `call` right away and then `period` after each call returns,
+// yielding every result, errors included. The stream does not end on its
+// own; drop it to stop polling.
+pub fn poll<T, F, Fut>(period: Duration, call: F) -> impl Stream<Item = Result<T, ExchangeError>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, ExchangeError>>,
+{
+    stream::unfold((call, true), move |(mut call, first)| async move {
+        if !first {
+            sleep(period).await;
+        }
+        let result = call().await;
+        Some((result, (call, false)))
+    })
+}
+
+impl BaseExchange {
+    pub fn watch_open_orders(&self, period: Duration) -> impl Stream<Item = Result<OpenOrders, ExchangeError>> + '_ {
+        poll(period, move || self.get_open_orders())
+    }
+}
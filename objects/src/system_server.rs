@@ -12,7 +12,7 @@ pub struct ServerTime {
 }
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SystemStatus {
-    status: String,
-    timestamp: String,
+    pub status: String,
+    pub timestamp: String,
 }
 
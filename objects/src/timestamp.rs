@@ -0,0 +1,88 @@
+use rust_decimal::{
+    prelude::ToPrimitive,
+    Decimal,
+};
+
+use serde::{
+    de::Error as _,
+    Deserialize,
+    Deserializer,
+    Serialize,
+    Serializer,
+};
+
+use std::{
+    fmt,
+    time::{
+        Duration,
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+
+
+
+/* Timestamp */
+// A time as the exchange reports it: seconds since the Unix epoch with a
+// fractional part, e.g. `1688666559.8974`. Read from numbers or strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(SystemTime);
+
+impl Timestamp {
+    // `None` for negative times or times past what `SystemTime` can hold.
+    pub fn from_unix_secs(secs: Decimal) -> Option<Self> {
+        if secs.is_sign_negative() {
+            return None;
+        }
+        let whole = secs.trunc().to_u64()?;
+        let nanos = (secs.fract() * Decimal::from(1_000_000_000u32)).round().to_u32()?;
+        UNIX_EPOCH.checked_add(Duration::new(whole, nanos)).map(Self)
+    }
+
+    pub fn unix_secs(&self) -> Decimal {
+        let since_epoch = self.0.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let secs = Decimal::from(since_epoch.as_secs()) + Decimal::new(since_epoch.subsec_nanos() as i64, 9);
+        secs.normalize()
+    }
+
+    pub fn system_time(&self) -> SystemTime {
+        self.0
+    }
+}
+
+impl From<SystemTime> for Timestamp {
+    fn from(time: SystemTime) -> Self {
+        Self(time)
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.unix_secs())
+    }
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.unix_secs().to_f64().unwrap_or_default())
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let secs = <Decimal as Deserialize>::deserialize(deserializer)?;
+        Timestamp::from_unix_secs(secs).ok_or_else(|| D::Error::custom(format!("Invalid timestamp {}", secs)))
+    }
+}
+
+// For `starttm`, `expiretm` and `closetm`, where 0 (or a missing field) means
+// the time is not set. Use with `#[serde(default)]`.
+pub fn zero_as_none<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Timestamp>, D::Error> {
+    match Option::<Decimal>::deserialize(deserializer)? {
+        Some(secs) if !secs.is_zero() => match Timestamp::from_unix_secs(secs) {
+            Some(timestamp) => Ok(Some(timestamp)),
+            None => Err(D::Error::custom(format!("Invalid timestamp {}", secs))),
+        },
+        _ => Ok(None),
+    }
+}
@@ -1,15 +1,149 @@
+use crate::{
+    orders::{
+        OrderType,
+        Side,
+    },
+    timestamp::Timestamp,
+};
+
+use rust_decimal::Decimal;
+
 use serde::{
     Deserialize,
     Serialize,
 };
 
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    fmt,
+    str::FromStr,
+};
 
 
 
 pub type TradingPairs = HashMap<String, TradingPair>;
 
-#[derive(Debug, Serialize, Deserialize)]
+/* Asset Pair Info */
+// The `info` parameter of `/public/AssetPairs`, selecting which fields are
+// returned for each pair.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum AssetPairsInfo {
+    // Every field, as `TradingPair`.
+    #[default]
+    Info,
+    Leverage,
+    Fees,
+    Margin,
+}
+
+impl fmt::Display for AssetPairsInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssetPairsInfo::Info => write!(f, "info"),
+            AssetPairsInfo::Leverage => write!(f, "leverage"),
+            AssetPairsInfo::Fees => write!(f, "fees"),
+            AssetPairsInfo::Margin => write!(f, "margin"),
+        }
+    }
+}
+
+impl FromStr for AssetPairsInfo {
+    type Err = String;
+
+    fn from_str(info: &str) -> Result<Self, Self::Err> {
+        match info {
+            "info" => Ok(AssetPairsInfo::Info),
+            "leverage" => Ok(AssetPairsInfo::Leverage),
+            "fees" => Ok(AssetPairsInfo::Fees),
+            "margin" => Ok(AssetPairsInfo::Margin),
+            _ => Err(format!("Unknown asset pairs info {}.", info)),
+        }
+    }
+}
+
+// The result of `/public/AssetPairs`, keyed by pair id, in the shape the
+// requested `AssetPairsInfo` selects.
+#[derive(Debug)]
+pub enum AssetPairs {
+    Info(TradingPairs),
+    Leverage(HashMap<String, PairLeverage>),
+    Fees(HashMap<String, PairFees>),
+    Margin(HashMap<String, PairMargin>),
+}
+
+impl AssetPairs {
+    pub fn info(&self) -> AssetPairsInfo {
+        match self {
+            AssetPairs::Info(_) => AssetPairsInfo::Info,
+            AssetPairs::Leverage(_) => AssetPairsInfo::Leverage,
+            AssetPairs::Fees(_) => AssetPairsInfo::Fees,
+            AssetPairs::Margin(_) => AssetPairsInfo::Margin,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            AssetPairs::Info(pairs) => pairs.len(),
+            AssetPairs::Leverage(pairs) => pairs.len(),
+            AssetPairs::Fees(pairs) => pairs.len(),
+            AssetPairs::Margin(pairs) => pairs.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairLeverage {
+    pub leverage_buy: Vec<i64>,
+    pub leverage_sell: Vec<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairFees {
+    // Fee tiers as (30 day volume, percent fee), lowest volume first.
+    pub fees: Vec<(Decimal, Decimal)>,
+    #[serde(default)]
+    pub fees_maker: Vec<(Decimal, Decimal)>,
+    pub fee_volume_currency: String,
+}
+
+impl PairFees {
+    // Percent fee for an account with the given 30 day volume.
+    pub fn taker_fee(&self, volume: Decimal) -> Option<Decimal> {
+        fee_for_volume(&self.fees, volume)
+    }
+
+    pub fn maker_fee(&self, volume: Decimal) -> Option<Decimal> {
+        fee_for_volume(&self.fees_maker, volume)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairMargin {
+    // Margin levels, in percent, for a margin call and a liquidation.
+    pub margin_call: i64,
+    pub margin_stop: i64,
+}
+
+/* Pair Status */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PairStatus {
+    Online,
+    CancelOnly,
+    PostOnly,
+    LimitOnly,
+    ReduceOnly,
+    // A status this version does not know yet.
+    #[serde(other)]
+    Unknown,
+}
+
+/* Trading Pair */
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradingPair {
     pub altname: String,
     pub wsname: String,
@@ -23,10 +157,126 @@ pub struct TradingPair {
     pub lot_multiplier: i64,
     pub leverage_buy: Vec<i64>,
     pub leverage_sell: Vec<i64>,
-    pub fees: Vec<Vec<f64>>,
-    pub fees_maker: Vec<Vec<f64>>,
+    // Fee tiers as (30 day volume, percent fee), lowest volume first.
+    pub fees: Vec<(Decimal, Decimal)>,
+    pub fees_maker: Vec<(Decimal, Decimal)>,
     pub fee_volume_currency: String,
     pub margin_call: i64,
     pub margin_stop: i64,
-    pub ordermin: String,
-}
\ No newline at end of file
+    // Minimum volume, in the base currency.
+    pub ordermin: Decimal,
+    // Minimum order value, in the quote currency.
+    #[serde(default)]
+    pub costmin: Option<Decimal>,
+    // Price increment. Older responses only have `pair_decimals`.
+    #[serde(default)]
+    pub tick_size: Option<Decimal>,
+    // Missing from older responses.
+    #[serde(default)]
+    pub status: Option<PairStatus>,
+    // Largest open margin position, in the base currency.
+    #[serde(default)]
+    pub long_position_limit: Option<i64>,
+    #[serde(default)]
+    pub short_position_limit: Option<i64>,
+}
+
+impl TradingPair {
+    pub fn price_increment(&self) -> Decimal {
+        match self.tick_size {
+            Some(tick_size) if !tick_size.is_zero() => tick_size,
+            _ => Decimal::new(1, self.pair_decimals as u32),
+        }
+    }
+
+    // Percent fee for an account with the given 30 day volume.
+    pub fn taker_fee(&self, volume: Decimal) -> Option<Decimal> {
+        fee_for_volume(&self.fees, volume)
+    }
+
+    pub fn maker_fee(&self, volume: Decimal) -> Option<Decimal> {
+        fee_for_volume(&self.fees_maker, volume)
+    }
+}
+
+fn fee_for_volume(tiers: &[(Decimal, Decimal)], volume: Decimal) -> Option<Decimal> {
+    tiers
+        .iter()
+        .take_while(|(min_volume, _)| *min_volume <= volume)
+        .last()
+        .map(|(_, fee)| *fee)
+}
+
+/* Ticker */
+pub type Tickers = HashMap<String, TickerInfo>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TickerInfo {
+    // Best ask as [price, whole lot volume, lot volume].
+    pub a: Vec<Decimal>,
+    // Best bid, as `a`.
+    pub b: Vec<Decimal>,
+    // Last trade as [price, lot volume].
+    pub c: Vec<Decimal>,
+    // Volume as [today, last 24 hours].
+    pub v: Vec<Decimal>,
+    // Volume weighted average price, as `v`.
+    pub p: Vec<Decimal>,
+    // Number of trades, as `v`.
+    pub t: Vec<i64>,
+    // Low, as `v`.
+    pub l: Vec<Decimal>,
+    // High, as `v`.
+    pub h: Vec<Decimal>,
+    // Today's opening price.
+    pub o: Decimal,
+}
+
+impl TickerInfo {
+    pub fn ask(&self) -> Option<Decimal> {
+        self.a.first().copied()
+    }
+
+    pub fn bid(&self) -> Option<Decimal> {
+        self.b.first().copied()
+    }
+
+    pub fn last(&self) -> Option<Decimal> {
+        self.c.first().copied()
+    }
+
+    pub fn volume_24h(&self) -> Option<Decimal> {
+        self.v.get(1).copied()
+    }
+}
+
+/* Trades History */
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TradesHistory {
+    pub trades: HashMap<String, TradeInfo>,
+    // Trades matching the query, which may be more than were returned.
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeInfo {
+    pub ordertxid: String,
+    #[serde(default)]
+    pub postxid: Option<String>,
+    // The pair id, e.g. `XXBTZUSD`.
+    pub pair: String,
+    pub time: Timestamp,
+    pub r#type: Side,
+    pub ordertype: OrderType,
+    pub price: Decimal,
+    pub cost: Decimal,
+    pub fee: Decimal,
+    pub vol: Decimal,
+    #[serde(default)]
+    pub margin: Option<Decimal>,
+    #[serde(default)]
+    pub misc: String,
+    #[serde(default)]
+    pub maker: Option<bool>,
+}
+
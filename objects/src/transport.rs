@@ -0,0 +1,167 @@
+use crate::{
+    errors::ExchangeError,
+    exchanges::PreparedRequest,
+};
+
+use async_trait::async_trait;
+
+use http::{
+    header::HeaderMap,
+    StatusCode,
+};
+
+use std::{
+    fmt,
+    time::Duration,
+};
+
+
+
+/* Transport Response */
+#[derive(Debug, Clone)]
+pub struct TransportResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+impl TransportResponse {
+    pub fn new(status: StatusCode, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            body: body.into(),
+        }
+    }
+}
+
+/* Transport */
+// Sends a fully prepared (and, for private endpoints, signed) request. Errors
+// are for requests that got no response at all; HTTP error statuses are
+// returned as responses.
+#[async_trait]
+pub trait Transport: fmt::Debug + Send + Sync {
+    async fn send(&self, request: PreparedRequest) -> Result<TransportResponse, ExchangeError>;
+}
+
+/* HTTP Client Settings */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpConfig {
+    pub connect_timeout: Option<Duration>,
+    // Covers the whole request, from connecting until the body is read.
+    pub request_timeout: Option<Duration>,
+    // Idle connections kept per host. `None` keeps reqwest's default.
+    pub pool_max_idle_per_host: Option<usize>,
+    pub pool_idle_timeout: Option<Duration>,
+    // HTTP(S) proxy URL for all requests, e.g. `http://proxy:3128`.
+    pub proxy: Option<String>,
+    pub user_agent: Option<String>,
+    pub tcp_keepalive: Option<Duration>,
+    // Talk HTTP/2 straight away instead of negotiating it. HTTPS connections
+    // already prefer HTTP/2 when the server offers it.
+    pub http2_prior_knowledge: bool,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Some(Duration::from_secs(10)),
+            request_timeout: Some(Duration::from_secs(30)),
+            pool_max_idle_per_host: None,
+            pool_idle_timeout: Some(Duration::from_secs(90)),
+            proxy: None,
+            user_agent: Some(concat!("objects/", env!("CARGO_PKG_VERSION")).to_string()),
+            tcp_keepalive: Some(Duration::from_secs(60)),
+            http2_prior_knowledge: false,
+        }
+    }
+}
+
+#[cfg(feature = "rest")]
+impl HttpConfig {
+    pub fn build_client(&self) -> Result<reqwest::Client, ExchangeError> {
+        let mut builder = reqwest::Client::builder()
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .tcp_keepalive(self.tcp_keepalive);
+        if let Some(connect_timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(connect_timeout);
+        }
+        if let Some(request_timeout) = self.request_timeout {
+            builder = builder.timeout(request_timeout);
+        }
+        if let Some(max_idle) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max_idle);
+        }
+        if let Some(proxy) = &self.proxy {
+            let proxy = reqwest::Proxy::all(proxy).map_err(|e| ExchangeError::Config(format!("Invalid proxy: {}", e)))?;
+            builder = builder.proxy(proxy);
+        }
+        if let Some(user_agent) = &self.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+        if self.http2_prior_knowledge {
+            builder = builder.http2_prior_knowledge();
+        }
+        builder.build().map_err(|e| ExchangeError::Config(e.to_string()))
+    }
+}
+
+/* Reqwest Transport */
+// Clones share the client's connection pool.
+#[cfg(feature = "rest")]
+#[derive(Debug, Clone)]
+pub struct ReqwestTransport {
+    pub client: reqwest::Client,
+}
+
+#[cfg(feature = "rest")]
+impl Default for ReqwestTransport {
+    fn default() -> Self {
+        let client = HttpConfig::default().build_client().expect("Error building default HTTP client.");
+        Self::new(client)
+    }
+}
+
+#[cfg(feature = "rest")]
+impl ReqwestTransport {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self {
+            client,
+        }
+    }
+}
+
+#[cfg(feature = "rest")]
+#[async_trait]
+impl Transport for ReqwestTransport {
+    async fn send(&self, request: PreparedRequest) -> Result<TransportResponse, ExchangeError> {
+        let r = match request.method.as_str() {
+            "GET" => self.client.get(request.url),
+            _ => self.client.post(request.url),
+        };
+        let r = r.headers(request.headers);
+        let resp = match request.body {
+            Some(body) => r.body(body).send().await,
+            None => r.send().await,
+        };
+        let resp = match resp {
+            Ok(res) => res,
+            Err(err) => {
+                return match err.status() {
+                    Some(status) => Err(ExchangeError::Status(status)),
+                    None => Err(ExchangeError::Request(err.to_string())),
+                };
+            }
+        };
+        let status = resp.status();
+        let headers = resp.headers().clone();
+        match resp.bytes().await {
+            Ok(body) => Ok(TransportResponse {
+                status,
+                headers,
+                body: body.to_vec(),
+            }),
+            Err(err) => Err(ExchangeError::Request(err.to_string())),
+        }
+    }
+}
@@ -0,0 +1,335 @@
+use crate::{
+    assets::{
+        AssetCatalog,
+        Pair,
+        PairFormat,
+    },
+    errors::ExchangeError,
+    exchanges::BaseExchange,
+    orders::{
+        NewOrder,
+        OrderFlag,
+        OrderStatus,
+        OrderType,
+        Side,
+    },
+    timestamp::Timestamp,
+};
+
+use async_trait::async_trait;
+
+use rust_decimal::Decimal;
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+};
+
+
+
+// Venue-neutral types for strategies and tools written once against
+// `Exchange`. Symbols are `BASE/QUOTE` with common asset codes, e.g.
+// `BTC/USD`, and ids are the venue's own.
+
+/* Market Data */
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Market {
+    pub symbol: String,
+    pub base: String,
+    pub quote: String,
+    pub price_increment: Decimal,
+    // Smallest order volume, in the base asset.
+    pub min_volume: Decimal,
+    pub volume_decimals: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Ticker {
+    pub symbol: String,
+    pub bid: Decimal,
+    pub ask: Decimal,
+    pub last: Decimal,
+    // Base asset volume over the last 24 hours.
+    pub volume: Decimal,
+}
+
+/* Balances */
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Balance {
+    pub asset: String,
+    pub total: Decimal,
+}
+
+/* Orders */
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderRequest {
+    pub symbol: String,
+    pub side: Side,
+    pub volume: Decimal,
+    // `None` for a market order.
+    pub price: Option<Decimal>,
+    pub post_only: bool,
+    pub client_id: Option<String>,
+}
+
+impl OrderRequest {
+    pub fn market(symbol: &str, side: Side, volume: Decimal) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            side,
+            volume,
+            price: None,
+            post_only: false,
+            client_id: None,
+        }
+    }
+
+    pub fn limit(symbol: &str, side: Side, volume: Decimal, price: Decimal) -> Self {
+        Self {
+            price: Some(price),
+            ..Self::market(symbol, side, volume)
+        }
+    }
+
+    pub fn post_only(mut self) -> Self {
+        self.post_only = true;
+        self
+    }
+
+    pub fn client_id(mut self, client_id: &str) -> Self {
+        self.client_id = Some(client_id.to_string());
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpenOrder {
+    pub id: String,
+    pub client_id: Option<String>,
+    pub symbol: String,
+    pub side: Side,
+    pub volume: Decimal,
+    pub filled: Decimal,
+    // `None` for market orders.
+    pub price: Option<Decimal>,
+    pub status: OrderStatus,
+    pub opened: Timestamp,
+}
+
+/* Fills */
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fill {
+    pub id: String,
+    pub order_id: String,
+    pub symbol: String,
+    pub side: Side,
+    pub price: Decimal,
+    pub volume: Decimal,
+    // In the quote asset.
+    pub fee: Decimal,
+    pub time: Timestamp,
+}
+
+/* Exchange */
+// What strategies and tools need from a venue. Implement it to add a venue;
+// `BaseExchange` is the adapter for the one this crate was written for.
+#[async_trait]
+pub trait Exchange: Send + Sync {
+    // Short name of the venue, e.g. for logs.
+    fn venue(&self) -> &str;
+
+    async fn markets(&self) -> Result<Vec<Market>, ExchangeError>;
+
+    async fn ticker(&self, symbol: &str) -> Result<Ticker, ExchangeError>;
+
+    // Assets with a non-zero balance.
+    async fn balances(&self) -> Result<Vec<Balance>, ExchangeError>;
+
+    // Returns the id of the new order.
+    async fn place_order(&self, order: OrderRequest) -> Result<String, ExchangeError>;
+
+    async fn open_orders(&self) -> Result<Vec<OpenOrder>, ExchangeError>;
+
+    async fn cancel_order(&self, order_id: &str) -> Result<(), ExchangeError>;
+
+    // Most recent first; `since` excludes older fills.
+    async fn fills(&self, since: Option<Timestamp>) -> Result<Vec<Fill>, ExchangeError>;
+}
+
+/* BaseExchange Adapter */
+// Symbols are resolved through the exchange's asset catalog, fetched on the
+// first call and kept. Call `BaseExchange::refresh_catalog` to pick up pairs
+// listed since. Orders and fills on pairs the catalog does not know keep the
+// pair name the exchange reported.
+#[async_trait]
+impl Exchange for BaseExchange {
+    fn venue(&self) -> &str {
+        match &self.venue {
+            Some(venue) => venue,
+            None => host(&self.base_url),
+        }
+    }
+
+    async fn markets(&self) -> Result<Vec<Market>, ExchangeError> {
+        let catalog = self.catalog().await?;
+        let mut markets: Vec<Market> = catalog
+            .pairs()
+            .filter_map(|pair| catalog.pair_info(pair.id()).ok().map(|info| (pair, info)))
+            .map(|(pair, info)| Market {
+                symbol: symbol(pair),
+                base: pair.base().symbol().to_string(),
+                quote: pair.quote().symbol().to_string(),
+                price_increment: info.price_increment(),
+                min_volume: info.ordermin,
+                volume_decimals: info.lot_decimals as u32,
+            })
+            .collect();
+        markets.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        Ok(markets)
+    }
+
+    async fn ticker(&self, symbol_name: &str) -> Result<Ticker, ExchangeError> {
+        let pair = self.catalog().await?.pair(symbol_name)?;
+        let tickers = self.get_ticker(vec![pair.format(PairFormat::Rest).to_string()]).await?;
+        let info = tickers
+            .get(pair.id())
+            .or_else(|| tickers.values().next())
+            .ok_or(ExchangeError::MissingResult)?;
+        let missing = |field: &str| ExchangeError::Decode(format!("Ticker for {} has no {}.", pair, field));
+        Ok(Ticker {
+            symbol: symbol(&pair),
+            bid: info.bid().ok_or_else(|| missing("bid"))?,
+            ask: info.ask().ok_or_else(|| missing("ask"))?,
+            last: info.last().ok_or_else(|| missing("last trade"))?,
+            volume: info.volume_24h().unwrap_or_default(),
+        })
+    }
+
+    async fn balances(&self) -> Result<Vec<Balance>, ExchangeError> {
+        let catalog = self.catalog().await?;
+        let mut balances: Vec<Balance> = self
+            .get_balance()
+            .await?
+            .into_iter()
+            .filter(|(_, total)| !total.is_zero())
+            .map(|(id, total)| Balance {
+                // Reward variants such as `XBT.F` are kept as reported.
+                asset: match catalog.asset(&id) {
+                    Ok(asset) => asset.symbol().to_string(),
+                    Err(_) => id,
+                },
+                total,
+            })
+            .collect();
+        balances.sort_by(|a, b| a.asset.cmp(&b.asset));
+        Ok(balances)
+    }
+
+    async fn place_order(&self, order: OrderRequest) -> Result<String, ExchangeError> {
+        let pair = self.catalog().await?.pair(&order.symbol)?;
+        let pair_name = pair.format(PairFormat::Rest);
+        let mut new_order = match order.price {
+            Some(price) => NewOrder::new(pair_name, order.side, OrderType::Limit, order.volume).price(price),
+            None => NewOrder::new(pair_name, order.side, OrderType::Market, order.volume),
+        };
+        if order.post_only {
+            new_order = new_order.oflag(OrderFlag::Post);
+        }
+        if let Some(client_id) = &order.client_id {
+            new_order = new_order.cl_ord_id(client_id);
+        }
+        let result = self.add_order(new_order).await?;
+        result.txid.into_iter().next().ok_or(ExchangeError::MissingResult)
+    }
+
+    async fn open_orders(&self) -> Result<Vec<OpenOrder>, ExchangeError> {
+        let catalog = self.catalog().await?;
+        let mut orders: Vec<OpenOrder> = self
+            .get_open_orders()
+            .await?
+            .open
+            .into_iter()
+            .map(|(id, order)| OpenOrder {
+                id,
+                client_id: order.cl_ord_id,
+                symbol: venue_symbol(&catalog, &order.descr.pair),
+                side: order.descr.r#type,
+                volume: order.vol,
+                filled: order.vol_exec,
+                price: match order.descr.ordertype {
+                    OrderType::Market => None,
+                    _ => Some(order.descr.price),
+                },
+                status: order.status,
+                opened: order.opentm,
+            })
+            .collect();
+        orders.sort_by_key(|order| order.opened);
+        Ok(orders)
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> Result<(), ExchangeError> {
+        match BaseExchange::cancel_order(self, order_id).await?.count {
+            0 => Err(ExchangeError::UnknownOrder(order_id.to_string())),
+            _ => Ok(()),
+        }
+    }
+
+    // Reads every page of the trades history. Trades made while paging shift
+    // later pages, so trades seen twice are kept once.
+    async fn fills(&self, since: Option<Timestamp>) -> Result<Vec<Fill>, ExchangeError> {
+        let catalog = self.catalog().await?;
+        let mut trades = HashMap::new();
+        let mut ofs = 0;
+        loop {
+            let page = self.get_trades_history_page(since, ofs).await?;
+            let returned = page.trades.len();
+            trades.extend(page.trades);
+            ofs += returned;
+            if returned == 0 || ofs as i64 >= page.count {
+                break;
+            }
+        }
+        let mut fills: Vec<Fill> = trades
+            .into_iter()
+            .map(|(id, trade)| Fill {
+                id,
+                order_id: trade.ordertxid,
+                symbol: venue_symbol(&catalog, &trade.pair),
+                side: trade.r#type,
+                price: trade.price,
+                volume: trade.vol,
+                fee: trade.fee,
+                time: trade.time,
+            })
+            .collect();
+        fills.sort_by_key(|fill| Reverse(fill.time));
+        Ok(fills)
+    }
+}
+
+fn symbol(pair: &Pair) -> String {
+    pair.format(PairFormat::Symbol).to_string()
+}
+
+// Pairs missing from the catalog, e.g. delisted ones, keep the name given.
+fn venue_symbol(catalog: &AssetCatalog, name: &str) -> String {
+    match catalog.pair(name) {
+        Ok(pair) => symbol(&pair),
+        Err(_) => name.to_string(),
+    }
+}
+
+// `api.example.com` for `https://api.example.com/0`.
+fn host(url: &str) -> &str {
+    let rest = match url.split_once("://") {
+        Some((_, rest)) => rest,
+        None => url,
+    };
+    rest.split('/').next().unwrap_or(rest)
+}
@@ -0,0 +1,244 @@
+use crate::{
+    config::Profile,
+    errors::ExchangeError,
+    secrets::{
+        SecretKey,
+        SecretString,
+    },
+};
+
+use argon2::{
+    Algorithm,
+    Argon2,
+    Params,
+    Version,
+};
+
+use base64::{
+    decode,
+    encode,
+};
+
+use chacha20poly1305::{
+    aead::{
+        rand_core::RngCore,
+        Aead,
+        KeyInit,
+        OsRng,
+    },
+    XChaCha20Poly1305,
+    XNonce,
+};
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use zeroize::Zeroize;
+
+
+
+const VAULT_VERSION: u32 = 1;
+const KEY_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+
+/* Key Derivation */
+// Argon2id cost parameters, stored in the vault so it can be reopened with
+// the parameters it was created with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfParams {
+    // Memory in KiB.
+    pub m_cost: u32,
+    pub t_cost: u32,
+    pub p_cost: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self {
+            m_cost: 64 * 1024,
+            t_cost: 3,
+            p_cost: 1,
+        }
+    }
+}
+
+impl KdfParams {
+    fn derive_key(&self, passphrase: &SecretString, salt: &[u8]) -> Result<SecretKey, ExchangeError> {
+        let params = Params::new(self.m_cost, self.t_cost, self.p_cost, Some(KEY_LEN)).map_err(vault_err)?;
+        let mut key = vec![0u8; KEY_LEN];
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.expose().as_bytes(), salt, &mut key)
+            .map_err(vault_err)?;
+        Ok(SecretKey::new(key))
+    }
+}
+
+/* Vault File */
+#[derive(Debug, Serialize, Deserialize)]
+struct VaultFile {
+    version: u32,
+    kdf: KdfParams,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/* Vault */
+// Profiles encrypted at rest with a key derived from a passphrase. The whole
+// profile map is one XChaCha20-Poly1305 message, re-encrypted under a fresh
+// nonce on every save.
+#[derive(Debug)]
+pub struct Vault {
+    path: PathBuf,
+    kdf: KdfParams,
+    salt: Vec<u8>,
+    key: SecretKey,
+    profiles: BTreeMap<String, Profile>,
+}
+
+impl Vault {
+    pub fn create(path: impl AsRef<Path>, passphrase: &SecretString) -> Result<Self, ExchangeError> {
+        Self::create_with_params(path, passphrase, KdfParams::default())
+    }
+
+    pub fn create_with_params(path: impl AsRef<Path>, passphrase: &SecretString, kdf: KdfParams) -> Result<Self, ExchangeError> {
+        let path = path.as_ref();
+        if path.exists() {
+            return Err(ExchangeError::Vault(format!("{} already exists.", path.display())));
+        }
+        let mut salt = vec![0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let key = kdf.derive_key(passphrase, &salt)?;
+        let vault = Self {
+            path: path.to_path_buf(),
+            kdf,
+            salt,
+            key,
+            profiles: BTreeMap::new(),
+        };
+        vault.save()?;
+        Ok(vault)
+    }
+
+    pub fn open(path: impl AsRef<Path>, passphrase: &SecretString) -> Result<Self, ExchangeError> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path).map_err(|e| ExchangeError::Vault(format!("{}: {}", path.display(), e)))?;
+        let file: VaultFile = serde_json::from_str(&contents).map_err(vault_err)?;
+        if file.version != VAULT_VERSION {
+            return Err(ExchangeError::Vault(format!("Unsupported vault version {}.", file.version)));
+        }
+        let salt = decode(&file.salt).map_err(vault_err)?;
+        let nonce = decode(&file.nonce).map_err(vault_err)?;
+        let ciphertext = decode(&file.ciphertext).map_err(vault_err)?;
+        if nonce.len() != NONCE_LEN {
+            return Err(ExchangeError::Vault("Invalid vault nonce.".to_string()));
+        }
+
+        let key = file.kdf.derive_key(passphrase, &salt)?;
+        let cipher = XChaCha20Poly1305::new_from_slice(key.expose()).map_err(vault_err)?;
+        let mut plaintext = cipher
+            .decrypt(XNonce::from_slice(&nonce), ciphertext.as_ref())
+            .map_err(|_| ExchangeError::Vault("Wrong passphrase or corrupted vault.".to_string()))?;
+        let profiles = serde_json::from_slice(&plaintext).map_err(vault_err);
+        plaintext.zeroize();
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            kdf: file.kdf,
+            salt,
+            key,
+            profiles: profiles?,
+        })
+    }
+
+    // Writes to a temporary file first so a failed save never truncates the vault.
+    pub fn save(&self) -> Result<(), ExchangeError> {
+        let mut nonce = vec![0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+        let cipher = XChaCha20Poly1305::new_from_slice(self.key.expose()).map_err(vault_err)?;
+        let mut plaintext = serde_json::to_vec(&self.profiles).map_err(vault_err)?;
+        let ciphertext = cipher.encrypt(XNonce::from_slice(&nonce), plaintext.as_ref()).map_err(vault_err);
+        plaintext.zeroize();
+
+        let file = VaultFile {
+            version: VAULT_VERSION,
+            kdf: self.kdf,
+            salt: encode(&self.salt),
+            nonce: encode(&nonce),
+            ciphertext: encode(ciphertext?),
+        };
+        let contents = serde_json::to_string_pretty(&file).map_err(vault_err)?;
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, contents).map_err(vault_err)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&tmp_path, fs::Permissions::from_mode(0o600)).map_err(vault_err)?;
+        }
+        fs::rename(&tmp_path, &self.path).map_err(vault_err)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &String> {
+        self.profiles.keys()
+    }
+
+    pub fn profile(&self, name: &str) -> Result<&Profile, ExchangeError> {
+        match self.profiles.get(name) {
+            Some(profile) => Ok(profile),
+            None => Err(ExchangeError::Vault(format!("Unknown profile {}.", name))),
+        }
+    }
+
+    // Adds a profile, failing if the name is taken.
+    pub fn add(&mut self, name: &str, profile: Profile) -> Result<(), ExchangeError> {
+        if self.profiles.contains_key(name) {
+            return Err(ExchangeError::Vault(format!("Profile {} already exists.", name)));
+        }
+        self.profiles.insert(name.to_string(), profile);
+        Ok(())
+    }
+
+    // Replaces a profile's key pair, keeping the rest of its settings.
+    pub fn rotate(&mut self, name: &str, api_key: &str, api_secret: SecretString) -> Result<(), ExchangeError> {
+        match self.profiles.get_mut(name) {
+            Some(profile) => {
+                profile.api_key = api_key.to_string();
+                profile.api_secret = api_secret;
+                Ok(())
+            },
+            None => Err(ExchangeError::Vault(format!("Unknown profile {}.", name))),
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) -> Result<Profile, ExchangeError> {
+        match self.profiles.remove(name) {
+            Some(profile) => Ok(profile),
+            None => Err(ExchangeError::Vault(format!("Unknown profile {}.", name))),
+        }
+    }
+
+    // Re-derives the key from a new passphrase and salt. Takes effect on `save`.
+    pub fn change_passphrase(&mut self, passphrase: &SecretString) -> Result<(), ExchangeError> {
+        let mut salt = vec![0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        self.key = self.kdf.derive_key(passphrase, &salt)?;
+        self.salt = salt;
+        Ok(())
+    }
+}
+
+fn vault_err(err: impl std::fmt::Display) -> ExchangeError {
+    ExchangeError::Vault(err.to_string())
+}
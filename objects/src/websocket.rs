@@ -0,0 +1,121 @@
+use crate::{
+    assets::{
+        Pair,
+        PairFormat,
+    },
+    encoding::Payload,
+    errors::ExchangeError,
+    exchanges::BaseExchange,
+    secrets::SecretString,
+};
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+
+
+// The client does not open WebSocket connections itself. This module has what
+// is needed to do so with any WebSocket library: a token for private channels
+// and the request messages of the v2 API. The endpoints are configured like
+// the REST one, see `BaseExchange::ws_public_url` and `ws_private_url`.
+
+/* Token */
+// Authenticates subscriptions to private channels. It must be used within
+// `expires` seconds; once used it stays valid while the connection is open.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebSocketsToken {
+    pub token: SecretString,
+    pub expires: u64,
+}
+
+impl BaseExchange {
+    pub async fn get_websockets_token(&self) -> Result<WebSocketsToken, ExchangeError> {
+        self.construct_req("/private/GetWebSocketsToken".to_string(), "POST", Payload::new()).await
+    }
+}
+
+/* Requests */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WsMethod {
+    Subscribe,
+    Unsubscribe,
+    Ping,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WsParams {
+    // e.g. `ticker`, `book`, `executions`.
+    pub channel: String,
+    // Pairs in `PairFormat::Symbol`, e.g. `BTC/USD`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub symbol: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+// Serialize with `serde_json` and send as a text frame.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WsRequest {
+    pub method: WsMethod,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<WsParams>,
+    // Echoed in the exchange's reply.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub req_id: Option<u64>,
+}
+
+impl WsRequest {
+    pub fn subscribe(channel: &str, pairs: &[Pair]) -> Self {
+        Self::with_params(WsMethod::Subscribe, channel, pairs)
+    }
+
+    pub fn unsubscribe(channel: &str, pairs: &[Pair]) -> Self {
+        Self::with_params(WsMethod::Unsubscribe, channel, pairs)
+    }
+
+    pub fn ping() -> Self {
+        Self {
+            method: WsMethod::Ping,
+            params: None,
+            req_id: None,
+        }
+    }
+
+    fn with_params(method: WsMethod, channel: &str, pairs: &[Pair]) -> Self {
+        let params = WsParams {
+            channel: channel.to_string(),
+            symbol: pairs.iter().map(|pair| pair.format(PairFormat::Symbol).to_string()).collect(),
+            ..Default::default()
+        };
+        Self {
+            method,
+            params: Some(params),
+            req_id: None,
+        }
+    }
+
+    // Needed for private channels such as `executions` and `balances`.
+    pub fn token(mut self, token: &WebSocketsToken) -> Self {
+        if let Some(params) = &mut self.params {
+            params.token = Some(token.token.expose().to_string());
+        }
+        self
+    }
+
+    pub fn snapshot(mut self, snapshot: bool) -> Self {
+        if let Some(params) = &mut self.params {
+            params.snapshot = Some(snapshot);
+        }
+        self
+    }
+
+    pub fn req_id(mut self, req_id: u64) -> Self {
+        self.req_id = Some(req_id);
+        self
+    }
+}
@@ -0,0 +1,67 @@
+use objects::{
+    errors::ExchangeError,
+    secrets::{
+        SecretKey,
+        SecretString,
+    },
+    signer::{
+        HmacSigner,
+        SigningDaemon,
+    },
+    vault::Vault,
+};
+
+use std::{
+    env::{
+        args,
+        var,
+    },
+    io::{
+        stdin,
+        BufRead,
+    },
+    process::exit,
+    sync::Arc,
+};
+
+
+
+const USAGE: &str = "\
+Usage: signer <vault> <profile> <socket> <endpoint>...
+
+Serves API-Sign values for one vault profile on a Unix socket, for the listed
+endpoints only (e.g. /private/AddOrder /private/CancelOrder). The vault
+passphrase is read from EXCHANGE_VAULT_PASSPHRASE or the first line of stdin.";
+
+fn main() {
+    let args: Vec<String> = args().skip(1).collect();
+    if let Err(e) = run(&args) {
+        eprintln!("{}", e);
+        exit(1);
+    }
+}
+
+fn run(args: &[String]) -> Result<(), ExchangeError> {
+    let (vault_path, name, socket, endpoints) = match args {
+        [vault_path, name, socket, endpoints @ ..] if !endpoints.is_empty() => (vault_path, name, socket, endpoints),
+        _ => return Err(ExchangeError::Signing(USAGE.to_string())),
+    };
+    let passphrase = match var("EXCHANGE_VAULT_PASSPHRASE") {
+        Ok(val) => SecretString::from(val),
+        Err(_) => match stdin().lock().lines().next() {
+            Some(Ok(line)) => SecretString::from(line.trim().to_string()),
+            _ => return Err(ExchangeError::Vault("Expected the passphrase on stdin.".to_string())),
+        },
+    };
+
+    let vault = Vault::open(vault_path, &passphrase)?;
+    let secret = SecretKey::from_base64(vault.profile(name)?.api_secret.expose())?;
+    drop(vault);
+
+    // Signed paths carry the API version prefix.
+    let allowed = endpoints.iter().map(|endpoint| format!("/0{}", endpoint));
+    let daemon = SigningDaemon::new(Arc::new(HmacSigner::new(secret)), allowed);
+    let listener = SigningDaemon::bind(socket)?;
+    println!("Signing for {} on {}.", name, socket);
+    daemon.serve(listener)
+}
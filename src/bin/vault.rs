@@ -0,0 +1,120 @@
+use objects::{
+    config::{
+        OtpConfig,
+        Profile,
+    },
+    errors::ExchangeError,
+    secrets::{
+        SecretKey,
+        SecretString,
+    },
+    vault::Vault,
+};
+
+use std::{
+    env::{
+        args,
+        var,
+    },
+    io::{
+        stdin,
+        BufRead,
+    },
+    process::exit,
+};
+
+
+
+const USAGE: &str = "\
+Usage: vault <file> <command>
+
+Commands:
+    init                                           create an empty vault
+    list                                           list profile names
+    add <name> <base_url> <api_key> [static|totp]  add a profile
+    rotate <name> <api_key>                        replace a profile's key pair
+    remove <name>                                  delete a profile
+
+The passphrase is read from EXCHANGE_VAULT_PASSPHRASE or the first line of
+stdin. API secrets and OTP secrets are read from the following stdin lines so
+they never appear in the shell history.";
+
+fn main() {
+    let args: Vec<String> = args().skip(1).collect();
+    if let Err(e) = run(&args) {
+        eprintln!("{}", e);
+        exit(1);
+    }
+}
+
+fn run(args: &[String]) -> Result<(), ExchangeError> {
+    let (path, command) = match args {
+        [path, command, ..] => (path, command.as_str()),
+        _ => return Err(usage()),
+    };
+    let rest = &args[2..];
+    let mut lines = stdin().lock().lines();
+    let mut read_secret = |what: &str| -> Result<SecretString, ExchangeError> {
+        match lines.next() {
+            Some(Ok(line)) if !line.trim().is_empty() => Ok(SecretString::from(line.trim().to_string())),
+            _ => Err(ExchangeError::Vault(format!("Expected the {} on stdin.", what))),
+        }
+    };
+    let passphrase = match var("EXCHANGE_VAULT_PASSPHRASE") {
+        Ok(val) => SecretString::from(val),
+        Err(_) => read_secret("passphrase")?,
+    };
+
+    match (command, rest) {
+        ("init", []) => {
+            Vault::create(path, &passphrase)?;
+            println!("Created {}.", path);
+        },
+        ("list", []) => {
+            let vault = Vault::open(path, &passphrase)?;
+            for name in vault.names() {
+                println!("{}", name);
+            }
+        },
+        ("add", [name, base_url, api_key, otp @ ..]) => {
+            let mut vault = Vault::open(path, &passphrase)?;
+            let api_secret = read_secret("API secret")?;
+            SecretKey::from_base64(api_secret.expose())?;
+            let otp = match otp {
+                [] => OtpConfig::None,
+                [method] if method == "static" => OtpConfig::Static { password: read_secret("OTP password")? },
+                [method] if method == "totp" => OtpConfig::Totp { secret: read_secret("TOTP secret")? },
+                _ => return Err(usage()),
+            };
+            vault.add(name, Profile {
+                base_url: base_url.to_string(),
+                api_key: api_key.to_string(),
+                api_secret,
+                otp,
+                ..Profile::default()
+            })?;
+            vault.save()?;
+            println!("Added {}.", name);
+        },
+        ("rotate", [name, api_key]) => {
+            let mut vault = Vault::open(path, &passphrase)?;
+            let api_secret = read_secret("API secret")?;
+            SecretKey::from_base64(api_secret.expose())?;
+            vault.rotate(name, api_key, api_secret)?;
+            vault.save()?;
+            println!("Rotated {}.", name);
+        },
+        ("remove", [name]) => {
+            let mut vault = Vault::open(path, &passphrase)?;
+            vault.remove(name)?;
+            vault.save()?;
+            println!("Removed {}.", name);
+        },
+        _ => return Err(usage()),
+    }
+    Ok(())
+}
+
+fn usage() -> ExchangeError {
+    ExchangeError::Vault(USAGE.to_string())
+}
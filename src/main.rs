@@ -1,12 +1,8 @@
 
 use async_trait::async_trait;
 
-use base64::{
-    decode,
-    encode,
-};
-
 use cucumber::{
+    gherkin::Step,
     given, 
     then,
     when,
@@ -15,29 +11,107 @@ use cucumber::{
     writer::Json,
 };
 
-use hmac::{
-    Hmac,
-    Mac,
-    NewMac,
-};
-
 use objects::{
+    assets::{
+        AssetCatalog,
+        PairFormat,
+    },
+    blocking::BlockingExchange,
+    cache::ResponseCache,
+    clock::{
+        ClockSample,
+        ClockSync,
+    },
+    config::{
+        Config,
+        Profile,
+    },
+    encoding::Payload,
     exchanges::{
-        BaseExchange
+        BaseExchange,
+        BaseExchangeBuilder,
+        PreparedRequest,
+    },
+    errors::ExchangeError,
+    middleware::{
+        ApiRequest,
+        LoggingMiddleware,
+        MetricsMiddleware,
+        Middleware,
+        Next,
+        RetryMiddleware,
+    },
+    mock::MemoryTransport,
+    nonce::{
+        FileNonce,
+        IncreasingNonce,
+        NonceProvider,
+        NonceResolution,
+    },
+    orders::{
+        AddOrderResult,
+        MiscFlag,
+        NewOrder,
+        OpenOrders,
+        OrderFlag,
+        OrderPrice,
+        OrderStatus,
+        OrderTime,
+        OrderType,
+        Rounding,
+        Side,
+    },
+    otp::{
+        OtpProvider,
+        StaticOtp,
+        Totp,
+    },
+    rate_limit::{
+        AccountTier,
+        RateLimitMode,
+        RateLimiter,
+    },
+    responses::APIResponse,
+    retry::RetryPolicy,
+    secrets::{
+        SecretKey,
+        SecretString,
+    },
+    signer::{
+        verify_signature,
+        DaemonSigner,
+        HmacSigner,
+        SigningDaemon,
+    },
+    timestamp::Timestamp,
+    trades::{
+        AssetPairs,
+        AssetPairsInfo,
+        PairStatus,
+        TradingPairs,
+    },
+    transport::{
+        HttpConfig,
+        TransportResponse,
     },
-    orders::OpenOrders,
-    responses::{
-        ResponseResult,
-        TradingPairResponse,
+    unified::{
+        Exchange,
+        OrderRequest,
     },
-    system_server::ServerTime,
+    vault::{
+        KdfParams,
+        Vault,
+    },
+    websocket::{
+        WebSocketsToken,
+        WsRequest,
+    },
+    Decimal,
 };
 
-use sha2::{
-    Digest,
-    Sha256,
-    Sha512,
-};
+use futures::StreamExt;
+
+use reqwest::StatusCode;
 
 use serde_json::{
     Error as jsonError,
@@ -54,57 +128,184 @@ use std::{
         read_to_string,
         self,
     },
-    path::Path,
+    io::{
+        BufRead,
+        BufReader,
+        Write,
+    },
+    net::TcpListener,
+    path::{
+        Path,
+        PathBuf,
+    },
+    process,
+    str::FromStr,
+    sync::{
+        atomic::{
+            AtomicUsize,
+            Ordering,
+        },
+        Arc,
+        Mutex,
+        OnceLock,
+    },
+    thread,
     time::{
-        SystemTime,
+        Duration,
+        Instant,
         UNIX_EPOCH,
     },
 };
 
 
 
-#[derive(Debug)]
-pub struct ServerTimeResponse {
-    server_time: ServerTime,
-    current_ts: i64,
-}
-
 #[derive(Debug)]
 pub struct Validate2FA {
-    private_key: String,
+    private_key: SecretKey,
     nonce: String,
     endpoint: String,
+    // Holds the OTP of 2FA requests.
+    body: SecretString,
 }
 
 impl Validate2FA {
     fn sign(&self, sign_to_match: String) -> bool {
-        let nonce = &self.nonce;
-        let payload = format!("{}nonce={}",nonce,nonce);
+        verify_signature(&self.private_key, &self.endpoint, &self.nonce, self.body.expose(), &sign_to_match)
+    }
+}
+
+#[derive(Debug)]
+pub struct SignedRequest {
+    secret: SecretKey,
+    endpoint: String,
+    nonce: String,
+    prepared: PreparedRequest,
+}
+
+impl SignedRequest {
+    fn verifies(&self) -> bool {
+        let body = self.prepared.body.as_ref().expect("Prepared private request has no body.");
+        verify_signature(&self.secret, &format!("/0{}",self.endpoint), &self.nonce, body, self.api_sign())
+    }
+
+    fn api_sign(&self) -> &str {
+        match self.prepared.headers.get("API-Sign") {
+            Some(val) => val.to_str().expect("Invalid API-Sign header."),
+            None => panic!("API-Sign header missing from prepared request."),
+        }
+    }
+}
 
-        let mut hashed_payload: Vec<u8> = Vec::new();
-        let mut sha_digest: Sha256 = Sha256::default();
-        sha_digest.update(nonce.to_string());
-        sha_digest.update(payload.to_string());
-        hashed_payload = sha_digest.finalize().to_vec();
+// Plain HTTP/1.1 server on a local port that answers every request with the
+// same server time over keep-alive connections. It records the connections
+// and requests it gets, so it also serves as an HTTP proxy in scenarios.
+#[derive(Debug, Clone)]
+pub struct LocalServer {
+    url: String,
+    connections: Arc<AtomicUsize>,
+    // Request line and user agent of each request.
+    requests: Arc<Mutex<Vec<(String, String)>>>,
+}
+
+impl LocalServer {
+    fn start(answer: bool) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Error binding local server.");
+        let server = Self {
+            url: format!("http://{}", listener.local_addr().expect("Local server has no address.")),
+            connections: Arc::new(AtomicUsize::new(0)),
+            requests: Arc::new(Mutex::new(Vec::new())),
+        };
+        let handle = server.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                handle.connections.fetch_add(1, Ordering::SeqCst);
+                let handle = handle.clone();
+                thread::spawn(move || handle.serve(stream, answer));
+            }
+        });
+        server
+    }
+
+    fn serve(&self, stream: std::net::TcpStream, answer: bool) {
+        let mut writer = match stream.try_clone() {
+            Ok(writer) => writer,
+            Err(_) => return,
+        };
+        let mut lines = BufReader::new(stream).lines();
+        while let Some(Ok(request_line)) = lines.next() {
+            let mut user_agent = String::new();
+            for line in lines.by_ref() {
+                let line = match line {
+                    Ok(line) if !line.is_empty() => line,
+                    _ => break,
+                };
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("user-agent") {
+                        user_agent = value.trim().to_string();
+                    }
+                }
+            }
+            self.requests.lock().unwrap().push((request_line, user_agent));
+            if !answer {
+                thread::sleep(Duration::from_secs(10));
+                return;
+            }
+            let body = r#"{"error": [], "result": {"unixtime": 1700000000, "rfc1123": "Tue, 14 Nov 23 22:13:20 +0000"}}"#;
+            let response = format!("HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}", body.len(), body);
+            if writer.write_all(response.as_bytes()).is_err() {
+                return;
+            }
+        }
+    }
+}
 
-        let pk_bytes: Vec<u8> = decode(&self.private_key).expect("Error decoding private_key.");
-        let mut hmac_512: Hmac<Sha512> = Hmac::<Sha512>::new_varkey(&pk_bytes).expect("Error creating Hmac<Sha512>.");
-        hmac_512.update(self.endpoint.as_bytes());
-        hmac_512.update(&hashed_payload);
-        
-        let signed = encode(hmac_512.finalize().into_bytes());
+// Test middleware that adds a parameter to every request.
+#[derive(Debug)]
+pub struct AddParam {
+    key: String,
+    value: String,
+}
 
-        signed == sign_to_match
+#[async_trait]
+impl Middleware for AddParam {
+    async fn handle(&self, mut request: ApiRequest, next: &mut Next<'_>) -> Result<TransportResponse, ExchangeError> {
+        request.payload.insert(&self.key, &self.value);
+        next.run(request).await
     }
 }
 
 #[derive(Debug, WorldInit)]
 pub struct ExchangeWorld {
     exchange: BaseExchange,
-    server_time_response: Option<ServerTimeResponse>,
-    trading_pair: Option<TradingPairResponse>,
+    clock_sample: Option<ClockSample>,
+    trading_pair: Option<TradingPairs>,
+    asset_pairs: Option<AssetPairs>,
     open_orders: Option<OpenOrders>,
     validate_2fa: Option<Validate2FA>,
+    signed_request: Option<SignedRequest>,
+    rate_limiter: Option<RateLimiter>,
+    accepted_calls: usize,
+    nonce_providers: Vec<Arc<dyn NonceProvider>>,
+    nonce_file: Option<PathBuf>,
+    issued_nonces: Vec<Vec<u64>>,
+    order_payload: Option<Payload>,
+    config: Option<Config>,
+    vault: Option<Vault>,
+    vault_file: Option<PathBuf>,
+    signer_secret: Option<SecretKey>,
+    signer_socket: Option<PathBuf>,
+    memory_transport: Option<Arc<MemoryTransport>>,
+    metrics: Option<Arc<MetricsMiddleware>>,
+    logging: Option<LoggingMiddleware>,
+    add_order_result: Option<Result<AddOrderResult, ExchangeError>>,
+    local_server: Option<LocalServer>,
+    exchanges: Vec<BaseExchange>,
+    catalog: Option<AssetCatalog>,
+    order_error: Option<ExchangeError>,
+    cache_snapshot: Option<PathBuf>,
+    cache_loaded: Option<usize>,
+    ws_token: Option<WebSocketsToken>,
+    unified_order_id: Option<String>,
 }
 
 impl ExchangeWorld {
@@ -114,41 +315,62 @@ impl ExchangeWorld {
             Ok(val) => val,
             Err(_) => "".to_string(),
         };
-        exchange.api_secret = match var("API_SECRET") {
-            Ok(val) => val,
-            Err(_) => "".to_string(),
-        };
+        exchange.api_secret = secret_from_env("API_SECRET");
         exchange.base_url = match var("BASE_URL") {
             Ok(val) => val,
             Err(_) => "".to_string(),
         };
-        exchange.api_passphrase = match var("API_PASSPHRASE") {
-            Ok(val) => {
+        exchange.otp = otp_from_env();
+        match exchange.otp {
+            Some(_) => {
                 exchange.api_key_2fa = match var("API_KEY_2FA") {
                     Ok(val) => val,
                     Err(_) => "".to_string(),
                 };
-                exchange.api_secret_2fa = match var("API_SECRET_2FA") {
-                    Ok(val) => val,
-                    Err(_) => "".to_string(),
-                };
-                val
+                exchange.api_secret_2fa = secret_from_env("API_SECRET_2FA");
             },
-            Err(_) => {
+            None => {
                 exchange.api_key_2fa = "".to_string();
-                exchange.api_secret_2fa = "".to_string();
-                "".to_string()
+                exchange.api_secret_2fa = SecretKey::default();
             }
         };
     }
 
     async fn populate_passphrase(&mut self) {
-        let exchange = &mut self.exchange;
-        exchange.api_passphrase = match var("API_PASSPHRASE") {
-            Ok(val) => val,
-            Err(_) => "".to_string(),
+        self.exchange.otp = otp_from_env();
+    }
+}
+
+// API secrets are decoded from base64 once, when they are loaded.
+fn secret_from_env(name: &str) -> SecretKey {
+    match var(name) {
+        Ok(val) => match SecretKey::from_base64(&val) {
+            Ok(secret) => secret,
+            Err(e) => panic!("Error reading {}: {}",name,e),
+        },
+        Err(_) => SecretKey::default(),
+    }
+}
+
+// API_TOTP_SECRET (base32 authenticator key) takes precedence over a static API_PASSPHRASE.
+fn otp_from_env() -> Option<Arc<dyn OtpProvider>> {
+    if let Ok(secret) = var("API_TOTP_SECRET") {
+        return match Totp::from_base32(&secret) {
+            Ok(totp) => Some(Arc::new(totp)),
+            Err(e) => panic!("Error reading API_TOTP_SECRET: {}",e),
         };
     }
+    match var("API_PASSPHRASE") {
+        Ok(val) => Some(Arc::new(StaticOtp::new(&val))),
+        Err(_) => None,
+    }
+}
+
+// Every scenario shares one HTTP client and its connection pool.
+fn shared_exchange() -> BaseExchange {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    let client = CLIENT.get_or_init(|| HttpConfig::default().build_client().expect("Error building HTTP client."));
+    BaseExchange::builder().client(client.clone()).build().expect("Error building exchange.")
 }
 
 #[async_trait(?Send)]
@@ -157,18 +379,43 @@ impl World for ExchangeWorld {
 
     async fn new() -> Result<Self, Infallible> {
         Ok(Self {
-            exchange: BaseExchange::default(),
-            server_time_response: None,
+            exchange: shared_exchange(),
+            clock_sample: None,
             trading_pair: None,
+            asset_pairs: None,
             open_orders: None,
             validate_2fa: None,
+            signed_request: None,
+            rate_limiter: None,
+            accepted_calls: 0,
+            nonce_providers: Vec::new(),
+            nonce_file: None,
+            issued_nonces: Vec::new(),
+            order_payload: None,
+            config: None,
+            vault: None,
+            vault_file: None,
+            signer_secret: None,
+            signer_socket: None,
+            memory_transport: None,
+            metrics: None,
+            logging: None,
+            add_order_result: None,
+            local_server: None,
+            exchanges: Vec::new(),
+            catalog: None,
+            order_error: None,
+            cache_snapshot: None,
+            cache_loaded: None,
+            ws_token: None,
+            unified_order_id: None,
         })
     }
 }
 
 #[given("an exchange instance")]
 async fn create_valid_exchange(w: &mut ExchangeWorld) {
-    w.exchange = BaseExchange::default();
+    w.exchange = shared_exchange();
 }
 
 #[when(regex = r"(API_KEY|API_KEY_2FA), (API_SECRET|API_SECRET_2FA), and (BASE_URL) exist")]
@@ -181,9 +428,8 @@ async fn check_env_vars_exist(w: &mut ExchangeWorld, api_key: String, api_secret
                         Ok(_) => {
                             if &api_key == "API_KEY_2FA" {
                                 w.exchange.api_passphrase_required = Some(true);
-                                match var("API_PASSPHRASE") {
-                                    Ok(_) => assert!(true),
-                                    _ => panic!("Error retrieving API_PASSPHRASE value. API_PASSPHRASE is missing and required for this exchange instance")
+                                if var("API_PASSPHRASE").is_err() {
+                                    panic!("Error retrieving API_PASSPHRASE value. API_PASSPHRASE is missing and required for this exchange instance")
                                 }
                             }
                         },
@@ -205,21 +451,9 @@ async fn populate_exchange_instance(w: &mut ExchangeWorld) {
 #[given("a server time request is sent")]
 async fn request_server_time(w: &mut ExchangeWorld) {
     let exchange = &mut w.exchange;
-    let response = exchange.get_server_time().await;
-    let current_ts = SystemTime::now().duration_since(UNIX_EPOCH).ok().unwrap().as_secs() as i64;
-    match response {
-        Ok(res) => {
-            let result = res.result;
-            match result {
-                ResponseResult::ServerTimeResponse(r) => {
-                    let server_time_response = ServerTimeResponse {
-                        server_time: r,
-                        current_ts: current_ts,
-                    };
-                    w.server_time_response = Some(server_time_response);
-                },
-                _ => panic!("Invalid ResponseResult received.")
-            }
+    match exchange.sync_clock().await {
+        Ok(sample) => {
+            w.clock_sample = Some(sample);
         },
         Err(e) => {
             panic!("{}",e);
@@ -229,32 +463,257 @@ async fn request_server_time(w: &mut ExchangeWorld) {
 
 #[when("a server time response is received")]
 async fn server_time_response_received(w: &mut ExchangeWorld) {
-    let server_time_response = &w.server_time_response;
-    match server_time_response {
-        Some(_) => assert!(true),
-        None => panic!("Server Time response not received.")
+    if w.clock_sample.is_none() {
+        panic!("Server Time response not received.")
     }
 }
 
-#[then("the response time in minutes should equal the server time in minutes")]
-async fn current_ts_matches_server_time(w: &mut ExchangeWorld) {
-    let server_time_response = &w.server_time_response;
-    match server_time_response {
-        Some(st_resp) => {
-            assert_eq!(st_resp.server_time.unixtime / 60, st_resp.current_ts / 60);
+#[then(expr = "the local clock should be within {int} seconds of the server time")]
+async fn local_clock_matches_server_time(w: &mut ExchangeWorld, seconds: u64) {
+    match &w.clock_sample {
+        Some(sample) => {
+            if sample.offset_ms.unsigned_abs() > seconds * 1000 {
+                panic!("Local clock is {}ms off the server time.",sample.offset_ms);
+            }
         },
         None => panic!("Error retrieving server time response.")
     }
 }
 
+#[given(expr = "a clock sync with a skew threshold of {int} seconds")]
+async fn create_clock_sync(w: &mut ExchangeWorld, seconds: u64) {
+    let mut clock = ClockSync::default();
+    clock.skew_threshold = Duration::from_secs(seconds);
+    w.exchange.clock = clock;
+}
+
+#[when(expr = "the server reports {int} for a request sent at {int}ms and answered at {int}ms")]
+async fn record_server_time(w: &mut ExchangeWorld, server_time: i64, sent: u64, received: u64) {
+    let sent = UNIX_EPOCH + Duration::from_millis(sent);
+    let received = UNIX_EPOCH + Duration::from_millis(received);
+    w.clock_sample = Some(w.exchange.clock.record(server_time, sent, received));
+}
+
+#[then(expr = "the clock offset should be {int}ms with a round trip of {int}ms")]
+async fn validate_clock_offset(w: &mut ExchangeWorld, offset: i64, round_trip: u64) {
+    let sample = w.clock_sample.as_ref().expect("Clock sample missing.");
+    assert_eq!(sample.offset_ms, offset);
+    assert_eq!(sample.round_trip, Duration::from_millis(round_trip));
+    assert_eq!(w.exchange.clock.offset.millis(), offset);
+}
+
+#[then(regex = r"the skew warning should (be|not be) raised")]
+async fn validate_skew_warning(w: &mut ExchangeWorld, raised: String) {
+    let sample = w.clock_sample.as_ref().expect("Clock sample missing.");
+    assert_eq!(sample.skew_exceeded, raised == "be");
+}
+
+#[when(regex = r"^an order (starting|expiring) (at local time|after) (\d+) seconds? is prepared$")]
+async fn prepare_timed_order(w: &mut ExchangeWorld, field: String, kind: String, seconds: u64) {
+    let order_time = match kind.as_str() {
+        "after" => OrderTime::After(Duration::from_secs(seconds)),
+        _ => OrderTime::At(UNIX_EPOCH + Duration::from_secs(seconds)),
+    };
+    let order = match field.as_str() {
+        "starting" => NewOrder::new("XBTUSD", Side::Buy, OrderType::Limit, Decimal::ONE).price(Decimal::new(100, 0)).starttm(order_time),
+        _ => NewOrder::new("XBTUSD", Side::Buy, OrderType::Limit, Decimal::ONE).price(Decimal::new(100, 0)).expiretm(order_time),
+    };
+    w.order_payload = Some(order.to_payload(&w.exchange.clock.offset));
+}
+
+#[then(expr = "the order {word} parameter should be {word}")]
+async fn validate_order_parameter(w: &mut ExchangeWorld, key: String, value: String) {
+    let payload = w.order_payload.as_ref().expect("Order payload missing.");
+    assert_eq!(payload.get(&key), Some(value.as_str()));
+}
+
+#[when(regex = r"^a (buy|sell) (\S+) order for (\S+) at (\S+)(?: with secondary price (\S+))? is prepared$")]
+async fn prepare_priced_order(w: &mut ExchangeWorld, side: String, ordertype: String, volume: String, price: String, price2: String) {
+    let side = match side.as_str() {
+        "buy" => Side::Buy,
+        _ => Side::Sell,
+    };
+    let ordertype = OrderType::from_str(&ordertype).expect("Invalid order type.");
+    let volume = Decimal::from_str(&volume).expect("Invalid volume.");
+    let mut order = NewOrder::new("XBTUSD", side, ordertype, volume).price(OrderPrice::from_str(&price).expect("Invalid price."));
+    if !price2.is_empty() {
+        order = order.price2(OrderPrice::from_str(&price2).expect("Invalid secondary price."));
+    }
+    w.order_payload = Some(order.to_payload(&w.exchange.clock.offset));
+}
+
+#[when(regex = r"^a (buy|sell) (\S+) order for (\S+) at (\S+) with flags (\S+) is prepared$")]
+async fn prepare_flagged_order(w: &mut ExchangeWorld, side: String, ordertype: String, volume: String, price: String, oflags: String) {
+    let side: Side = from_str(&format!("{:?}",side)).expect("Invalid side.");
+    let ordertype = OrderType::from_str(&ordertype).expect("Invalid order type.");
+    let volume = Decimal::from_str(&volume).expect("Invalid volume.");
+    let mut order = NewOrder::new("XBTUSD", side, ordertype, volume).price(OrderPrice::from_str(&price).expect("Invalid price."));
+    for flag in oflags.split(',') {
+        order = order.oflag(OrderFlag::from_str(flag).expect("Invalid flag."));
+    }
+    w.order_payload = Some(order.to_payload(&w.exchange.clock.offset));
+}
+
+#[when(regex = r"^a ((?:buy|sell) \S+) order for (\S+) at (\S+)(?: with leverage (\S+))? is (fitted to|checked against) (\S+)$")]
+async fn validate_order_for_pair(w: &mut ExchangeWorld, kind: String, volume: String, price: String, leverage: String, rounding: String, pair: String) {
+    let pairs = w.trading_pair.as_ref().expect("Asset pairs missing.");
+    let pair = pairs.get(&pair).expect("Pair missing from response.");
+    let (side, ordertype) = kind.split_once(' ').expect("Invalid order kind.");
+    let side: Side = from_str(&format!("{:?}",side)).expect("Invalid side.");
+    let ordertype = OrderType::from_str(ordertype).expect("Invalid order type.");
+    let volume = Decimal::from_str(&volume).expect("Invalid volume.");
+    let mut order = NewOrder::new(&pair.altname, side, ordertype, volume).price(OrderPrice::from_str(&price).expect("Invalid price."));
+    if !leverage.is_empty() {
+        order = order.leverage(&leverage);
+    }
+    let rounding = match rounding.as_str() {
+        "fitted to" => Rounding::Round,
+        _ => Rounding::Reject,
+    };
+    match order.validated(pair, rounding) {
+        Ok(order) => {
+            w.order_payload = Some(order.to_payload(&w.exchange.clock.offset));
+            w.order_error = None;
+        },
+        Err(e) => {
+            w.order_payload = None;
+            w.order_error = Some(e);
+        },
+    }
+}
+
+#[then(expr = "the order should be accepted")]
+async fn validate_order_accepted(w: &mut ExchangeWorld) {
+    assert!(w.order_error.is_none(), "Order refused: {:?}", w.order_error);
+}
+
+#[then(expr = "the order should be refused with {string}")]
+async fn validate_order_refused(w: &mut ExchangeWorld, message: String) {
+    match &w.order_error {
+        Some(ExchangeError::InvalidOrder(err)) => assert_eq!(err, &message),
+        other => panic!("Expected the order to be refused, got {:?}", other),
+    }
+}
+
+#[then(expr = "the order price {word} should be rejected")]
+async fn validate_invalid_order_price(_w: &mut ExchangeWorld, price: String) {
+    assert!(OrderPrice::from_str(&price).is_err());
+}
+
+#[given("the asset pairs response")]
+async fn load_asset_pairs_response(w: &mut ExchangeWorld, #[step] step: &Step) {
+    let body = step.docstring().expect("Response docstring missing.");
+    let response: APIResponse<TradingPairs> = from_str(body).expect("Error decoding asset pairs.");
+    w.trading_pair = Some(response.into_result().expect("Asset pairs response has errors."));
+}
+
+#[then(regex = r"^the (\S+) (taker|maker) fee at a volume of (\S+) should be (\S+)$")]
+async fn validate_pair_fee(w: &mut ExchangeWorld, pair: String, kind: String, volume: String, fee: String) {
+    let pairs = w.trading_pair.as_ref().expect("Asset pairs missing.");
+    let pair = pairs.get(&pair).expect("Pair missing from response.");
+    let volume = Decimal::from_str(&volume).expect("Invalid volume.");
+    let actual = match kind.as_str() {
+        "taker" => pair.taker_fee(volume),
+        _ => pair.maker_fee(volume),
+    };
+    assert_eq!(actual, Some(Decimal::from_str(&fee).expect("Invalid fee.")));
+}
+
+#[then(expr = "the {word} minimum order should be {word}")]
+async fn validate_pair_ordermin(w: &mut ExchangeWorld, pair: String, ordermin: String) {
+    let pairs = w.trading_pair.as_ref().expect("Asset pairs missing.");
+    let pair = pairs.get(&pair).expect("Pair missing from response.");
+    assert_eq!(pair.ordermin, Decimal::from_str(&ordermin).expect("Invalid minimum."));
+}
+
+#[given("the open orders response")]
+async fn load_open_orders_response(w: &mut ExchangeWorld, #[step] step: &Step) {
+    let body = step.docstring().expect("Response docstring missing.");
+    let response: APIResponse<OpenOrders> = from_str(body).expect("Error decoding open orders.");
+    w.open_orders = Some(response.into_result().expect("Open orders response has errors."));
+}
+
+#[then(expr = "order {word} should have {word} left to execute")]
+async fn validate_order_remaining(w: &mut ExchangeWorld, txid: String, remaining: String) {
+    let orders = w.open_orders.as_ref().expect("Open orders missing.");
+    let order = orders.open.get(&txid).expect("Order missing from response.");
+    assert_eq!(order.remaining(), Decimal::from_str(&remaining).expect("Invalid volume."));
+}
+
+#[then(expr = "order {word} should have cost {word} including fees")]
+async fn validate_order_total_cost(w: &mut ExchangeWorld, txid: String, total: String) {
+    let orders = w.open_orders.as_ref().expect("Open orders missing.");
+    let order = orders.open.get(&txid).expect("Order missing from response.");
+    assert_eq!((order.cost + order.fee).to_string(), total);
+}
+
+#[then(regex = r"^order (\S+) should be an? (open|closed|pending|canceled|expired) (buy|sell) (\S+) order$")]
+async fn validate_order_kind(w: &mut ExchangeWorld, txid: String, status: String, side: String, ordertype: String) {
+    let orders = w.open_orders.as_ref().expect("Open orders missing.");
+    let order = orders.open.get(&txid).expect("Order missing from response.");
+    let status: OrderStatus = from_str(&format!("{:?}",status)).expect("Invalid status.");
+    let side: Side = from_str(&format!("{:?}",side)).expect("Invalid side.");
+    assert_eq!(order.status, status);
+    assert_eq!(order.descr.r#type, side);
+    assert_eq!(order.descr.ordertype, OrderType::from_str(&ordertype).expect("Invalid order type."));
+}
+
+#[then(regex = r"^order (\S+) should have been opened at (\S+)$")]
+async fn validate_order_opentm(w: &mut ExchangeWorld, txid: String, opentm: String) {
+    let orders = w.open_orders.as_ref().expect("Open orders missing.");
+    let order = orders.open.get(&txid).expect("Order missing from response.");
+    assert_eq!(order.opentm.unix_secs(), Decimal::from_str(&opentm).expect("Invalid time."));
+}
+
+#[then(regex = r"^order (\S+) should (start|expire) at (\S+)$")]
+async fn validate_order_time(w: &mut ExchangeWorld, txid: String, field: String, time: String) {
+    let orders = w.open_orders.as_ref().expect("Open orders missing.");
+    let order = orders.open.get(&txid).expect("Order missing from response.");
+    let actual = match field.as_str() {
+        "start" => order.starttm,
+        _ => order.expiretm,
+    };
+    match time.as_str() {
+        "never" => assert!(actual.is_none()),
+        time => assert_eq!(actual.map(|t| t.unix_secs()), Some(Decimal::from_str(time).expect("Invalid time."))),
+    }
+}
+
+#[then(regex = r"^order (\S+) should have the order flags (\S+) and misc flags (\S+)$")]
+async fn validate_order_flags(w: &mut ExchangeWorld, txid: String, oflags: String, misc: String) {
+    let orders = w.open_orders.as_ref().expect("Open orders missing.");
+    let order = orders.open.get(&txid).expect("Order missing from response.");
+    let expected: Vec<OrderFlag> = oflags.split(',').map(|f| OrderFlag::from_str(f).expect("Invalid flag.")).collect();
+    assert_eq!(order.oflags.iter().copied().collect::<Vec<OrderFlag>>(), expected);
+    let expected: Vec<MiscFlag> = match misc.as_str() {
+        "none" => Vec::new(),
+        misc => misc.split(',').map(|f| MiscFlag::from_str(f).expect("Invalid flag.")).collect(),
+    };
+    assert_eq!(order.misc.iter().copied().collect::<Vec<MiscFlag>>(), expected);
+}
+
+#[then(regex = r"^order (\S+) should (have|not have) a stop price$")]
+async fn validate_order_stopprice(w: &mut ExchangeWorld, txid: String, context: String) {
+    let orders = w.open_orders.as_ref().expect("Open orders missing.");
+    let order = orders.open.get(&txid).expect("Order missing from response.");
+    assert_eq!(order.stopprice.is_some(), context == "have");
+}
+
 #[given(regex = r"a (?P<base>[A-Z]{3})/(?P<quote>[A-Z]{3}) trading pair request is sent")]
 async fn request_trading_pair(w: &mut ExchangeWorld, base: String, quote: String) {
     let exchange = &mut w.exchange;
-    let response = exchange.get_tradable_asset_pairs(vec![format!("{}{}",base,quote)], None).await;
+    let pair = match exchange.catalog().await.and_then(|catalog| catalog.pair_of(&base, &quote)) {
+        Ok(pair) => pair,
+        Err(e) => panic!("{}",e),
+    };
+    let response = exchange.get_tradable_asset_pairs(vec![pair.format(PairFormat::Rest).to_string()], AssetPairsInfo::Info).await;
     match response {
-        Ok(res) => {
+        Ok(AssetPairs::Info(res)) => {
             w.trading_pair = Some(res);
         },
+        Ok(other) => {
+            panic!("Expected full asset pair information, got {}.", other.info());
+        },
         Err(e) => {
             panic!("{}",e);
         }
@@ -270,23 +729,343 @@ async fn trading_pair_response_received(w: &mut ExchangeWorld, base: String, quo
 
 #[then(regex = "the response should contain (?P<base>[A-Z]{3})/(?P<quote>[A-Z]{3}) asset pair information")]
 async fn trading_pairs_validate(w: &mut ExchangeWorld, base: String, quote: String) {
-    let trading_pair_results = &mut w.trading_pair.as_ref().unwrap().result.values();
+    let trading_pairs = w.trading_pair.as_ref().unwrap();
+    if trading_pairs.len() != 1 {
+        panic!("Error retrieving first trading pair result.")
+    }
 
-    let trading_pair = match trading_pair_results.len() {
-        1 => {
-            trading_pair_results.nth(0).unwrap()
-        },
-        _ => panic!("Error retrieving first trading pair result.")
+    let assets = match w.exchange.get_assets(Vec::new()).await {
+        Ok(assets) => assets,
+        Err(e) => panic!("{}",e),
+    };
+    let catalog = AssetCatalog::new(&assets, trading_pairs);
+    match catalog.pair_of(&base, &quote) {
+        Ok(pair) if trading_pairs.contains_key(pair.id()) => (),
+        _ => panic!("Invalid trading pair."),
+    }
+}
+
+#[when(expr = "the {word} asset pair {word} is requested")]
+async fn request_asset_pair_info(w: &mut ExchangeWorld, info: String, pair: String) {
+    let info = AssetPairsInfo::from_str(&info).expect("Invalid asset pairs info.");
+    w.asset_pairs = Some(w.exchange.get_tradable_asset_pairs(vec![pair], info).await.expect("Error requesting asset pairs."));
+}
+
+#[then(regex = r"^the (\S+) leverage should be (\S*) to buy and (\S*) to sell$")]
+async fn validate_pair_leverage(w: &mut ExchangeWorld, pair: String, buy: String, sell: String) {
+    let leverage = match w.asset_pairs.as_ref().expect("Asset pairs missing.") {
+        AssetPairs::Leverage(pairs) => pairs.get(&pair).expect("Pair missing from response."),
+        other => panic!("Expected leverage information, got {}.", other.info()),
+    };
+    let levels = |levels: &[i64]| levels.iter().map(|l| l.to_string()).collect::<Vec<String>>().join(",");
+    assert_eq!(levels(&leverage.leverage_buy), buy);
+    assert_eq!(levels(&leverage.leverage_sell), sell);
+}
+
+#[then(regex = r"^the (\S+) fees should be (\S+) taker and (\S+) maker at a volume of (\S+)$")]
+async fn validate_pair_fees(w: &mut ExchangeWorld, pair: String, taker: String, maker: String, volume: String) {
+    let fees = match w.asset_pairs.as_ref().expect("Asset pairs missing.") {
+        AssetPairs::Fees(pairs) => pairs.get(&pair).expect("Pair missing from response."),
+        other => panic!("Expected fee information, got {}.", other.info()),
     };
+    let volume = Decimal::from_str(&volume).expect("Invalid volume.");
+    assert_eq!(fees.taker_fee(volume), Some(Decimal::from_str(&taker).expect("Invalid fee.")));
+    assert_eq!(fees.maker_fee(volume), Some(Decimal::from_str(&maker).expect("Invalid fee.")));
+}
+
+#[then(expr = "the {word} margin call should be at {int}% and liquidation at {int}%")]
+async fn validate_pair_margin(w: &mut ExchangeWorld, pair: String, margin_call: i64, margin_stop: i64) {
+    let margin = match w.asset_pairs.as_ref().expect("Asset pairs missing.") {
+        AssetPairs::Margin(pairs) => pairs.get(&pair).expect("Pair missing from response."),
+        other => panic!("Expected margin information, got {}.", other.info()),
+    };
+    assert_eq!(margin.margin_call, margin_call);
+    assert_eq!(margin.margin_stop, margin_stop);
+}
+
+#[then(regex = r"^the (\S+) pair should be (\S+)(?: with a long position limit of (\d+))?$")]
+async fn validate_pair_status(w: &mut ExchangeWorld, pair: String, status: String, long_limit: String) {
+    let pair = match w.asset_pairs.as_ref().expect("Asset pairs missing.") {
+        AssetPairs::Info(pairs) => pairs.get(&pair).expect("Pair missing from response."),
+        other => panic!("Expected full asset pair information, got {}.", other.info()),
+    };
+    let expected: PairStatus = from_str(&format!("{:?}",status)).expect("Invalid pair status.");
+    assert_eq!(pair.status, Some(expected));
+    if !long_limit.is_empty() {
+        assert_eq!(pair.long_position_limit, Some(long_limit.parse().expect("Invalid position limit.")));
+    }
+}
 
-    if trading_pair.altname != format!("{}{}",base,quote) {
-        panic!("Invalid trading pair.")
+#[given(expr = "the exchange caches {word} for {int} second(s)")]
+async fn add_exchange_cache(w: &mut ExchangeWorld, href: String, ttl: u64) {
+    w.exchange.cache = Some(Arc::new(ResponseCache::new().with_ttl(&href, Duration::from_secs(ttl))));
+}
+
+#[when(expr = "the asset pairs are requested {int} time(s)")]
+async fn request_asset_pairs_repeatedly(w: &mut ExchangeWorld, times: usize) {
+    for _ in 0..times {
+        match w.exchange.get_tradable_asset_pairs(Vec::new(), AssetPairsInfo::Info).await {
+            Ok(AssetPairs::Info(pairs)) => w.trading_pair = Some(pairs),
+            other => panic!("Error requesting asset pairs: {:?}", other),
+        }
+    }
+}
+
+#[when(expr = "{int} tasks request the asset pairs at once")]
+async fn request_asset_pairs_concurrently(w: &mut ExchangeWorld, tasks: usize) {
+    let exchange = Arc::new(w.exchange.clone());
+    let handles: Vec<_> = (0..tasks)
+        .map(|_| {
+            let exchange = exchange.clone();
+            tokio::spawn(async move { exchange.get_tradable_asset_pairs(Vec::new(), AssetPairsInfo::Info).await })
+        })
+        .collect();
+    for handle in handles {
+        handle.await.expect("Task panicked.").expect("Error requesting asset pairs.");
     }
 }
 
+#[when(expr = "the exchange's base URL is changed to {word}")]
+async fn change_base_url(w: &mut ExchangeWorld, base_url: String) {
+    w.exchange.base_url = base_url;
+}
+
+#[then(expr = "the response cache should have {int} call(s) in flight")]
+async fn validate_cache_in_flight(w: &mut ExchangeWorld, count: usize) {
+    assert_eq!(w.exchange.cache.as_ref().expect("Response cache missing.").in_flight(), count);
+}
+
+#[when(expr = "the cached {word} responses are invalidated")]
+async fn invalidate_cache(w: &mut ExchangeWorld, href: String) {
+    w.exchange.cache.as_ref().expect("Response cache missing.").invalidate(&href);
+}
+
+static CACHE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+#[when("the cache is saved to a snapshot")]
+async fn save_cache_snapshot(w: &mut ExchangeWorld) {
+    let snapshot = std::env::temp_dir().join(format!(
+        "exchange_interface_cache_{}_{}.json",
+        process::id(),
+        CACHE_COUNT.fetch_add(1, Ordering::SeqCst),
+    ));
+    let cache = w.exchange.cache.as_ref().expect("Response cache missing.");
+    cache.save_snapshot(&snapshot).expect("Error saving cache snapshot.");
+    w.cache_snapshot = Some(snapshot);
+}
+
+#[when(expr = "a new exchange caching {word} for {int} second(s) is warmed from the snapshot")]
+async fn warm_cache_from_snapshot(w: &mut ExchangeWorld, href: String, ttl: u64) {
+    let transport = Arc::new(MemoryTransport::new());
+    w.exchange = BaseExchange::builder()
+        .base_url("https://api.example.com/0")
+        .transport(transport.clone())
+        .cache(Arc::new(ResponseCache::new().with_ttl(&href, Duration::from_secs(ttl))))
+        .build()
+        .expect("Error building exchange.");
+    w.memory_transport = Some(transport);
+    let snapshot = w.cache_snapshot.take().expect("Cache snapshot missing.");
+    let loaded = w.exchange.cache.as_ref().unwrap().load_snapshot(&snapshot).expect("Error loading cache snapshot.");
+    let _ = fs::remove_file(&snapshot);
+    w.cache_loaded = Some(loaded);
+}
+
+#[then(expr = "{int} cached response(s) should have been loaded")]
+async fn validate_cache_loaded(w: &mut ExchangeWorld, loaded: usize) {
+    assert_eq!(w.cache_loaded, Some(loaded));
+}
+
+#[then(expr = "the asset pairs should include {word}")]
+async fn validate_asset_pairs_include(w: &mut ExchangeWorld, pair: String) {
+    let pairs = w.trading_pair.as_ref().expect("Asset pairs missing.");
+    assert!(pairs.contains_key(&pair), "{} missing from asset pairs.", pair);
+}
+
+#[when(expr = "open orders are watched for {int} polls")]
+async fn watch_open_orders(w: &mut ExchangeWorld, polls: usize) {
+    let results: Vec<_> = w.exchange.watch_open_orders(Duration::from_millis(10)).take(polls).collect().await;
+    assert_eq!(results.len(), polls);
+    for result in results {
+        result.expect("Error polling open orders.");
+    }
+}
+
+#[given(expr = "an exchange built with WebSocket URLs {word} and {word}")]
+async fn create_websocket_exchange(w: &mut ExchangeWorld, public: String, private: String) {
+    w.exchange = BaseExchange::builder()
+        .base_url("https://api.example.com/0")
+        .transport(Arc::new(MemoryTransport::new()))
+        .websocket_urls(&public, &private)
+        .build()
+        .expect("Error building exchange.");
+}
+
+#[then(expr = "the exchange's WebSocket URLs should be {word} and {word}")]
+async fn validate_websocket_urls(w: &mut ExchangeWorld, public: String, private: String) {
+    assert_eq!(w.exchange.ws_public_url, public);
+    assert_eq!(w.exchange.ws_private_url, private);
+}
+
+#[when("a WebSockets token is requested")]
+async fn request_websockets_token(w: &mut ExchangeWorld) {
+    w.ws_token = Some(w.exchange.get_websockets_token().await.expect("Error requesting WebSockets token."));
+}
+
+#[then(expr = "the WebSockets token should expire in {int} seconds")]
+async fn validate_websockets_token(w: &mut ExchangeWorld, expires: u64) {
+    let token = w.ws_token.as_ref().expect("WebSockets token missing.");
+    assert!(!token.token.is_empty());
+    assert_eq!(token.expires, expires);
+}
+
+#[then(regex = r"^the (subscribe|unsubscribe) request for (\S+) on (\S+)( with the token)? should be$")]
+async fn validate_ws_request(w: &mut ExchangeWorld, #[step] step: &Step, method: String, channel: String, pairs: String, with_token: String) {
+    let catalog = w.catalog.as_ref().expect("Asset catalog missing.");
+    let pairs: Vec<_> = pairs.split(',').map(|pair| catalog.pair(pair).expect("Pair did not resolve.")).collect();
+    let mut request = match method.as_str() {
+        "subscribe" => WsRequest::subscribe(&channel, &pairs),
+        _ => WsRequest::unsubscribe(&channel, &pairs),
+    };
+    if !with_token.is_empty() {
+        request = request.token(w.ws_token.as_ref().expect("WebSockets token missing.")).req_id(7);
+    }
+    let expected: Value = from_str(step.docstring().expect("Request docstring missing.")).expect("Invalid JSON.");
+    assert_eq!(serde_json::to_value(&request).expect("Error serializing request."), expected);
+}
+
+// Unified steps go through the trait, as a strategy written for any venue would.
+fn venue(w: &ExchangeWorld) -> &dyn Exchange {
+    &w.exchange
+}
+
+#[then(expr = "the {word} market should trade {word} against {word} in steps of {word}")]
+async fn validate_unified_market(w: &mut ExchangeWorld, symbol: String, base: String, quote: String, tick: String) {
+    let markets = venue(w).markets().await.expect("Error requesting markets.");
+    let market = markets.iter().find(|m| m.symbol == symbol).expect("Market missing.");
+    assert_eq!((market.base.as_str(), market.quote.as_str()), (base.as_str(), quote.as_str()));
+    assert_eq!(market.price_increment, Decimal::from_str(&tick).expect("Invalid tick."));
+}
+
+#[then(expr = "the {word} ticker should have bid {word}, ask {word} and last {word}")]
+async fn validate_unified_ticker(w: &mut ExchangeWorld, symbol: String, bid: String, ask: String, last: String) {
+    let ticker = venue(w).ticker(&symbol).await.expect("Error requesting ticker.");
+    assert_eq!(ticker.symbol, "BTC/USD");
+    assert_eq!(ticker.bid.to_string(), bid);
+    assert_eq!(ticker.ask.to_string(), ask);
+    assert_eq!(ticker.last.to_string(), last);
+}
+
+#[then(expr = "the unified balances should be {word}")]
+async fn validate_unified_balances(w: &mut ExchangeWorld, expected: String) {
+    let balances = venue(w).balances().await.expect("Error requesting balances.");
+    let actual: Vec<String> = balances.iter().map(|b| format!("{}={}", b.asset, b.total)).collect();
+    assert_eq!(actual.join(","), expected);
+}
+
+#[when(regex = r"^a unified (buy|sell) order for (\S+) (\S+)(?: at (\S+))? is placed$")]
+async fn place_unified_order(w: &mut ExchangeWorld, side: String, volume: String, symbol: String, price: String) {
+    let side: Side = from_str(&format!("{:?}",side)).expect("Invalid side.");
+    let volume = Decimal::from_str(&volume).expect("Invalid volume.");
+    let order = match price.is_empty() {
+        true => OrderRequest::market(&symbol, side, volume),
+        false => OrderRequest::limit(&symbol, side, volume, Decimal::from_str(&price).expect("Invalid price.")).post_only(),
+    };
+    w.unified_order_id = Some(venue(w).place_order(order).await.expect("Error placing order."));
+}
+
+#[then(expr = "the unified order id should be {word}")]
+async fn validate_unified_order_id(w: &mut ExchangeWorld, id: String) {
+    assert_eq!(w.unified_order_id.as_deref(), Some(id.as_str()));
+}
+
+#[then(expr = "unified open order {word} should be a {word} of {word} {word} at {word}")]
+async fn validate_unified_open_order(w: &mut ExchangeWorld, id: String, side: String, volume: String, symbol: String, price: String) {
+    let orders = venue(w).open_orders().await.expect("Error requesting open orders.");
+    let order = orders.iter().find(|o| o.id == id).expect("Order missing.");
+    assert_eq!(order.side.to_string(), side);
+    assert_eq!(order.volume.to_string(), volume);
+    assert_eq!(order.symbol, symbol);
+    assert_eq!(order.price.map(|p| p.to_string()).unwrap_or_else(|| "market".to_string()), price);
+}
+
+#[then(regex = r"^canceling unified order (\S+) should (succeed|fail as an unknown order)$")]
+async fn cancel_unified_order(w: &mut ExchangeWorld, id: String, outcome: String) {
+    match (venue(w).cancel_order(&id).await, outcome.as_str()) {
+        (Ok(()), "succeed") => {},
+        (Err(ExchangeError::UnknownOrder(unknown)), "fail as an unknown order") => assert_eq!(unknown, id),
+        (result, _) => panic!("Expected to {}, got {:?}", outcome, result),
+    }
+}
+
+#[then(expr = "the unified venue should be {word}")]
+async fn validate_unified_venue(w: &mut ExchangeWorld, expected: String) {
+    assert_eq!(venue(w).venue(), expected);
+}
+
+#[then(expr = "the unified fills should be {word}")]
+async fn validate_unified_fills(w: &mut ExchangeWorld, expected: String) {
+    let fills = venue(w).fills(None).await.expect("Error requesting fills.");
+    let actual: Vec<String> = fills
+        .iter()
+        .map(|f| format!("{}:{}:{}:{}@{}", f.id, f.order_id, f.symbol, f.volume, f.price))
+        .collect();
+    assert_eq!(actual.join(","), expected);
+}
+
+#[when("the asset catalog is loaded")]
+async fn load_catalog(w: &mut ExchangeWorld) {
+    w.catalog = Some(w.exchange.get_catalog().await.expect("Error loading asset catalog."));
+}
+
+#[when("the exchange's asset catalog is refreshed")]
+async fn refresh_catalog(w: &mut ExchangeWorld) {
+    w.exchange.refresh_catalog().await.expect("Error refreshing asset catalog.");
+}
+
+#[then(expr = "the asset {word} should resolve to {word}")]
+async fn validate_asset_alias(w: &mut ExchangeWorld, name: String, id: String) {
+    let catalog = w.catalog.as_ref().expect("Asset catalog missing.");
+    assert_eq!(catalog.asset(&name).expect("Asset did not resolve.").id(), id);
+}
+
+#[then(expr = "the pair {word} should resolve to {word}")]
+async fn validate_pair_alias(w: &mut ExchangeWorld, name: String, id: String) {
+    let catalog = w.catalog.as_ref().expect("Asset catalog missing.");
+    assert_eq!(catalog.pair(&name).expect("Pair did not resolve.").id(), id);
+}
+
+#[then(expr = "the pair trading {word} against {word} should be {word}")]
+async fn validate_pair_of(w: &mut ExchangeWorld, base: String, quote: String, id: String) {
+    let catalog = w.catalog.as_ref().expect("Asset catalog missing.");
+    assert_eq!(catalog.pair_of(&base, &quote).expect("Pair did not resolve.").id(), id);
+}
+
+#[then(regex = r"^the (asset|pair) (\S+) should not resolve$")]
+async fn validate_unknown_alias(w: &mut ExchangeWorld, kind: String, name: String) {
+    let catalog = w.catalog.as_ref().expect("Asset catalog missing.");
+    let result = match kind.as_str() {
+        "asset" => catalog.asset(&name).map(|_| ()),
+        _ => catalog.pair(&name).map(|_| ()),
+    };
+    assert!(matches!(result, Err(ExchangeError::Symbol(_))));
+}
+
+#[then(regex = r"^the pair (\S+) should be written (\S+) for (Id|Rest|WebSocket|Symbol)$")]
+async fn validate_pair_format(w: &mut ExchangeWorld, name: String, written: String, format: String) {
+    let catalog = w.catalog.as_ref().expect("Asset catalog missing.");
+    let pair = catalog.pair(&name).expect("Pair did not resolve.");
+    let format = match format.as_str() {
+        "Id" => PairFormat::Id,
+        "Rest" => PairFormat::Rest,
+        "WebSocket" => PairFormat::WebSocket,
+        _ => PairFormat::Symbol,
+    };
+    assert_eq!(pair.format(format), written);
+}
+
 #[given("API_PASSPHRASE exists")]
 async fn check_api_passphrase(w: &mut ExchangeWorld) {
-    match var("API_PASSPHRASE") {
+    match var("API_PASSPHRASE").or_else(|_| var("API_TOTP_SECRET")) {
         Ok(_) => {
             w.populate_passphrase().await;
         },
@@ -296,12 +1075,13 @@ async fn check_api_passphrase(w: &mut ExchangeWorld) {
     }
 }
 
-#[when(expr = "using {word}, {word}, and {word} for sign testing")]
-async fn gathering_signing_values(w: &mut ExchangeWorld, private_key: String, nonce: String, endpoint: String) {
+#[when(expr = "using {word}, {word}, {word}, and {word} for sign testing")]
+async fn gathering_signing_values(w: &mut ExchangeWorld, private_key: String, nonce: String, endpoint: String, body: String) {
     let validate_2fa = Validate2FA {
-        private_key: private_key,
-        nonce: nonce,
-        endpoint: endpoint,
+        private_key: SecretKey::from_base64(&private_key).expect("Error decoding private_key."),
+        nonce,
+        endpoint,
+        body: SecretString::from(body),
     };
     w.validate_2fa = Some(validate_2fa);
 }
@@ -314,67 +1094,988 @@ async fn validating_signed_value(w: &mut ExchangeWorld, signed: String) {
     }
 }
 
+#[given(expr = "an exchange instance signing with {word}")]
+async fn create_signing_exchange(w: &mut ExchangeWorld, secret: String) {
+    w.exchange = shared_exchange();
+    w.exchange.api_key = "API_KEY".to_string();
+    w.exchange.api_secret = SecretKey::from_base64(&secret).expect("Invalid secret.");
+    w.exchange.api_passphrase_required = Some(false);
+}
+
+#[when(expr = "a private {word} request is prepared with nonce {word} and parameters {word}")]
+async fn prepare_private_request(w: &mut ExchangeWorld, endpoint: String, nonce: String, params: String) {
+    let mut payload = Payload::new();
+    payload.insert("nonce", &nonce);
+    for param in params.split(',') {
+        match param.split_once('=') {
+            Some((key, val)) => payload.push(key, val),
+            None => panic!("Invalid parameter {}.", param),
+        };
+    }
+    let prepared = w.exchange.prepare_req(endpoint.clone(), "POST", payload).await.expect("Error preparing request.");
+    // A daemon-signed exchange holds no secret; recompute with the daemon's.
+    let secret = match (&w.signer_secret, w.exchange.api_pass_required()) {
+        (Some(secret), _) => secret.clone(),
+        (None, true) => w.exchange.api_secret_2fa.clone(),
+        (None, false) => w.exchange.api_secret.clone(),
+    };
+    w.signed_request = Some(SignedRequest {
+        secret,
+        endpoint,
+        nonce,
+        prepared,
+    });
+}
+
+#[then(expr = "the sent body should be {word}")]
+async fn validate_sent_body(w: &mut ExchangeWorld, body: String) {
+    let signed_request = w.signed_request.as_ref().unwrap();
+    assert_eq!(signed_request.prepared.body.as_deref(), Some(body.as_str()));
+}
+
+#[then(expr = "the API-Sign header should be {word}")]
+async fn validate_api_sign(w: &mut ExchangeWorld, signed: String) {
+    let signed_request = w.signed_request.as_ref().unwrap();
+    assert_eq!(signed_request.api_sign(), signed);
+}
+
+#[then("the API-Sign header should verify against the sent body")]
+async fn validate_api_sign_against_body(w: &mut ExchangeWorld) {
+    let signed_request = w.signed_request.as_ref().unwrap();
+    assert!(signed_request.verifies());
+}
+
+// Scenarios run concurrently, so each daemon gets its own socket.
+static SIGNER_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+#[given(expr = "an exchange instance signing through a daemon holding {word} for {word}")]
+async fn create_daemon_signing_exchange(w: &mut ExchangeWorld, secret: String, endpoint: String) {
+    let secret = SecretKey::from_base64(&secret).expect("Invalid secret.");
+    let socket = std::env::temp_dir().join(format!(
+        "exchange_interface_signer_{}_{}.sock",
+        process::id(),
+        SIGNER_COUNT.fetch_add(1, Ordering::SeqCst),
+    ));
+    let daemon = SigningDaemon::new(Arc::new(HmacSigner::new(secret.clone())), vec![format!("/0{}",endpoint)]);
+    let listener = SigningDaemon::bind(&socket).expect("Error binding signer socket.");
+    thread::spawn(move || daemon.serve(listener));
+
+    w.exchange = shared_exchange();
+    w.exchange.api_key = "API_KEY".to_string();
+    w.exchange.api_passphrase_required = Some(false);
+    w.exchange.signer = Some(Arc::new(DaemonSigner::new(&socket)));
+    w.signer_secret = Some(secret);
+    w.signer_socket = Some(socket);
+}
+
+#[then("the signer socket should only be accessible to its owner")]
+async fn validate_signer_socket_mode(w: &mut ExchangeWorld) {
+    use std::os::unix::fs::PermissionsExt;
+    let socket = w.signer_socket.as_ref().expect("Signer socket missing.");
+    let mode = fs::metadata(socket).expect("Error reading signer socket.").permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+}
+
+#[then(expr = "{int} private {word} requests signed through the daemon at once should all verify")]
+async fn validate_concurrent_daemon_signing(w: &mut ExchangeWorld, requests: usize, endpoint: String) {
+    let exchange = Arc::new(w.exchange.clone());
+    let handles: Vec<_> = (0..requests)
+        .map(|_| {
+            let exchange = exchange.clone();
+            let endpoint = endpoint.clone();
+            tokio::spawn(async move { exchange.prepare_req(endpoint, "POST", Payload::new()).await })
+        })
+        .collect();
+    let secret = w.signer_secret.as_ref().expect("Signing secret missing.");
+    for handle in handles {
+        let prepared = handle.await.expect("Task panicked.").expect("Error preparing request.");
+        let body = prepared.body.as_deref().expect("Signed request has no body.");
+        let nonce = body
+            .split('&')
+            .find_map(|param| param.strip_prefix("nonce="))
+            .expect("Signed request has no nonce.");
+        let api_sign = prepared.headers.get("API-Sign").expect("API-Sign header missing.").to_str().expect("Invalid API-Sign header.");
+        assert!(verify_signature(secret, &format!("/0{}",endpoint), nonce, body, api_sign));
+    }
+}
+
+#[then("the exchange should not hold an API secret")]
+async fn validate_no_secret(w: &mut ExchangeWorld) {
+    assert!(w.exchange.api_secret.is_empty());
+    assert!(w.exchange.api_secret_2fa.is_empty());
+}
+
+#[then(expr = "preparing a private {word} request should fail with a signing error")]
+async fn validate_refused_signature(w: &mut ExchangeWorld, endpoint: String) {
+    match w.exchange.prepare_req(endpoint, "POST", Payload::new()).await {
+        Err(ExchangeError::Signing(_)) => {},
+        other => panic!("Expected a signing error, got {:?}", other),
+    }
+}
+
+#[then(regex = r"^the API-Sign header should not verify (with the secret|for the path|with the nonce|with the body) (\S+)$")]
+async fn validate_api_sign_rejected(w: &mut ExchangeWorld, changed: String, value: String) {
+    let signed_request = w.signed_request.as_ref().unwrap();
+    let mut secret = signed_request.secret.clone();
+    let mut path = format!("/0{}",signed_request.endpoint);
+    let mut nonce = signed_request.nonce.clone();
+    let mut body = signed_request.prepared.body.clone().expect("Prepared private request has no body.");
+    match changed.as_str() {
+        "with the secret" => secret = SecretKey::from_base64(&value).expect("Invalid secret."),
+        "for the path" => path = value,
+        "with the nonce" => nonce = value,
+        _ => body = value,
+    }
+    assert!(!verify_signature(&secret, &path, &nonce, &body, signed_request.api_sign()));
+}
+
+#[then(expr = "the signature {word} should not verify")]
+async fn validate_malformed_sign(w: &mut ExchangeWorld, sign: String) {
+    let signed_request = w.signed_request.as_ref().unwrap();
+    let body = signed_request.prepared.body.as_ref().expect("Prepared private request has no body.");
+    let path = format!("/0{}",signed_request.endpoint);
+    assert!(!verify_signature(&signed_request.secret, &path, &signed_request.nonce, body, &sign));
+}
+
+#[given(expr = "an exchange instance with an in-memory transport signing with {word}")]
+async fn create_memory_exchange(w: &mut ExchangeWorld, secret: String) {
+    let secret = SecretKey::from_base64(&secret).expect("Invalid secret.");
+    let transport = Arc::new(MemoryTransport::new());
+    w.exchange = BaseExchange::builder()
+        .base_url("https://api.example.com/0")
+        .transport(transport.clone())
+        .build()
+        .expect("Error building exchange.");
+    w.exchange.api_key = "API_KEY".to_string();
+    w.exchange.api_secret = secret.clone();
+    w.exchange.api_passphrase_required = Some(false);
+    w.signer_secret = Some(secret);
+    w.memory_transport = Some(transport);
+}
+
+#[given(expr = "the transport answers {word} with")]
+async fn add_transport_response(w: &mut ExchangeWorld, path: String, #[step] step: &Step) {
+    let body = step.docstring().expect("Response docstring missing.");
+    w.memory_transport.as_ref().expect("In-memory transport missing.").respond_json(&path, body.trim());
+}
+
+#[given(expr = "the transport answers {word} with HTTP status {int}")]
+async fn add_transport_status(w: &mut ExchangeWorld, path: String, status: u16) {
+    let status = StatusCode::from_u16(status).expect("Invalid HTTP status.");
+    w.memory_transport.as_ref().expect("In-memory transport missing.").respond(&path, TransportResponse::new(status, ""));
+}
+
+#[then(expr = "the server time from the transport should be {int}")]
+async fn validate_transport_server_time(w: &mut ExchangeWorld, unixtime: i64) {
+    let server_time = w.exchange.get_server_time().await.expect("Error requesting server time.");
+    assert_eq!(server_time.unixtime, unixtime);
+}
+
+#[then(expr = "requesting open orders should fail with HTTP status {int}")]
+async fn validate_transport_status(w: &mut ExchangeWorld, status: u16) {
+    match w.exchange.get_open_orders().await {
+        Err(ExchangeError::Status(s)) => assert_eq!(s.as_u16(), status),
+        other => panic!("Expected HTTP status {}, got {:?}", status, other),
+    }
+}
+
+#[then(regex = r"^the transport should have received (\d+) (GET|POST) requests? to (\S+)(?: on (\S+))?( with a valid API-Sign)?$")]
+async fn validate_transport_requests(w: &mut ExchangeWorld, count: usize, method: String, path: String, host: String, signed: String) {
+    let requests = w.memory_transport.as_ref().expect("In-memory transport missing.").requests();
+    let host = match host.is_empty() {
+        true => "https://api.example.com".to_string(),
+        false => host,
+    };
+    let url = format!("{}{}",host,path);
+    let matching: Vec<&PreparedRequest> = requests
+        .iter()
+        .filter(|r| r.method == method && r.url.split('?').next() == Some(url.as_str()))
+        .collect();
+    assert_eq!(matching.len(), count);
+    if signed.is_empty() {
+        return;
+    }
+    let secret = w.signer_secret.as_ref().expect("Signing secret missing.");
+    for request in matching {
+        let body = request.body.as_deref().expect("Signed request has no body.");
+        let nonce = body
+            .split('&')
+            .find_map(|param| param.strip_prefix("nonce="))
+            .expect("Signed request has no nonce.");
+        let api_sign = request.headers.get("API-Sign").expect("API-Sign header missing.").to_str().expect("Invalid API-Sign header.");
+        assert!(verify_signature(secret, &path, nonce, body, api_sign));
+    }
+}
+
+#[given(expr = "a retry middleware with {int} attempts")]
+async fn add_retry_middleware(w: &mut ExchangeWorld, attempts: u32) {
+    w.exchange.middlewares.push(Arc::new(RetryMiddleware::new(test_retry_policy(attempts))));
+}
+
+// Short delays keep the scenarios fast.
+fn test_retry_policy(attempts: u32) -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: attempts,
+        backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(5),
+        ..RetryPolicy::default()
+    }
+}
+
+#[given(expr = "a retry policy with {int} attempts")]
+async fn set_retry_policy(w: &mut ExchangeWorld, attempts: u32) {
+    w.exchange.retry_policy = Some(test_retry_policy(attempts));
+}
+
+#[when(regex = r"^an order(?: for (\S+))?(?: at (\S+))? is placed(?: with (client order id|userref) (\S+))?$")]
+async fn place_order(w: &mut ExchangeWorld, pair: String, price: String, id_kind: String, id: String) {
+    let pair = match pair.is_empty() {
+        true => "XBTUSD",
+        false => pair.as_str(),
+    };
+    let price = match price.is_empty() {
+        true => Decimal::new(37500, 0),
+        false => Decimal::from_str(&price).expect("Invalid price."),
+    };
+    let mut order = NewOrder::new(pair, Side::Buy, OrderType::Limit, Decimal::new(125, 2)).price(price);
+    order = match id_kind.as_str() {
+        "client order id" => order.cl_ord_id(&id),
+        "userref" => order.userref(id.parse().expect("Invalid userref.")),
+        _ => order,
+    };
+    w.add_order_result = Some(w.exchange.add_order(order).await);
+}
+
+#[when(expr = "{int} orders alternating between {word} and {word} are placed at once")]
+async fn place_orders_concurrently(w: &mut ExchangeWorld, orders: usize, first: String, second: String) {
+    let exchange = Arc::new(w.exchange.clone());
+    let handles: Vec<_> = (0..orders)
+        .map(|i| {
+            let exchange = exchange.clone();
+            let pair = match i % 2 {
+                0 => first.clone(),
+                _ => second.clone(),
+            };
+            let order = NewOrder::new(&pair, Side::Buy, OrderType::Limit, Decimal::new(125, 2)).price(Decimal::new(37500, 0));
+            tokio::spawn(async move { exchange.add_order(order).await })
+        })
+        .collect();
+    for handle in handles {
+        handle.await.expect("Task panicked.").expect("Error placing order.");
+    }
+}
+
+// The blocking client starts its own runtime, which cannot happen on a
+// thread that drives async tasks.
+fn run_blocking<T, F>(exchange: &BaseExchange, call: F) -> T
+where
+    T: Send + 'static,
+    F: FnOnce(&BlockingExchange) -> T + Send + 'static,
+{
+    let exchange = exchange.clone();
+    thread::spawn(move || {
+        let client = BlockingExchange::new(exchange).expect("Error creating blocking client.");
+        call(&client)
+    })
+    .join()
+    .expect("Blocking client panicked.")
+}
+
+#[when("the blocking client places an order")]
+async fn place_order_blocking(w: &mut ExchangeWorld) {
+    let order = NewOrder::new("XBTUSD", Side::Buy, OrderType::Limit, Decimal::new(125, 2)).price(Decimal::new(37500, 0));
+    w.add_order_result = Some(run_blocking(&w.exchange, move |client| client.add_order(order)));
+}
+
+#[when("the blocking client requests open orders")]
+async fn request_open_orders_blocking(w: &mut ExchangeWorld) {
+    let open_orders = run_blocking(&w.exchange, |client| client.get_open_orders());
+    w.open_orders = Some(open_orders.expect("Error requesting open orders."));
+}
+
+#[then(expr = "the blocking client should read the server time {int}")]
+async fn validate_server_time_blocking(w: &mut ExchangeWorld, unixtime: i64) {
+    let server_time = run_blocking(&w.exchange, |client| client.get_server_time());
+    assert_eq!(server_time.expect("Error requesting server time.").unixtime, unixtime);
+}
+
+#[then(expr = "the blocking client should find order {word} by userref {int} since {int}")]
+async fn validate_userref_lookup_blocking(w: &mut ExchangeWorld, txid: String, userref: i32, since: i64) {
+    let since = Timestamp::from_unix_secs(Decimal::from(since)).expect("Invalid timestamp.");
+    let found = run_blocking(&w.exchange, move |client| client.find_order_by_userref(userref, since));
+    let (found_txid, _) = found.expect("Error looking up order.").expect("Order not found.");
+    assert_eq!(found_txid, txid);
+}
+
+#[then(expr = "the blocking client should report an order-rate headroom of {int} for {word}")]
+async fn validate_order_rate_headroom_blocking(w: &mut ExchangeWorld, headroom: i64, pair: String) {
+    let order_headroom = run_blocking(&w.exchange, move |client| client.order_rate_headroom(&pair));
+    assert_eq!(order_headroom.expect("Rate limiter missing.").remaining.round() as i64, headroom);
+}
+
+#[then(expr = "the blocking client should fail to request open orders with HTTP status {int}")]
+async fn validate_status_blocking(w: &mut ExchangeWorld, status: u16) {
+    match run_blocking(&w.exchange, |client| client.get_open_orders()) {
+        Err(ExchangeError::Status(s)) => assert_eq!(s.as_u16(), status),
+        other => panic!("Expected HTTP status {}, got {:?}", status, other),
+    }
+}
+
+#[then(expr = "the order should have been placed as {word}")]
+async fn validate_order_placed(w: &mut ExchangeWorld, txid: String) {
+    match w.add_order_result.as_ref().expect("No order placed.") {
+        Ok(result) => assert_eq!(result.txid, vec![txid]),
+        Err(e) => panic!("Expected order {}, got error {}", txid, e),
+    }
+}
+
+#[then(expr = "placing the order should have failed with HTTP status {int}")]
+async fn validate_order_failed(w: &mut ExchangeWorld, status: u16) {
+    match w.add_order_result.as_ref().expect("No order placed.") {
+        Err(ExchangeError::Status(s)) => assert_eq!(s.as_u16(), status),
+        other => panic!("Expected HTTP status {}, got {:?}", status, other),
+    }
+}
+
+#[then(expr = "placing the order should have been refused with {string}")]
+async fn validate_order_placement_refused(w: &mut ExchangeWorld, message: String) {
+    match w.add_order_result.as_ref().expect("No order placed.") {
+        Err(ExchangeError::InvalidOrder(err)) => assert_eq!(err, &message),
+        other => panic!("Expected the order to be refused, got {:?}", other),
+    }
+}
+
+#[then(expr = "every POST request to {word} should carry the same client order id")]
+async fn validate_same_cl_ord_id(w: &mut ExchangeWorld, path: String) {
+    let mut ids: Vec<String> = sent_bodies(w, &path)
+        .iter()
+        .map(|body| body.split('&').find_map(|p| p.strip_prefix("cl_ord_id=")).expect("Missing cl_ord_id.").to_string())
+        .collect();
+    assert!(!ids.is_empty());
+    ids.dedup();
+    assert_eq!(ids.len(), 1);
+}
+
+#[given("a metrics middleware")]
+async fn add_metrics_middleware(w: &mut ExchangeWorld) {
+    let metrics = Arc::new(MetricsMiddleware::new());
+    w.exchange.middlewares.push(metrics.clone());
+    w.metrics = Some(metrics);
+}
+
+#[given("a logging middleware")]
+async fn add_logging_middleware(w: &mut ExchangeWorld) {
+    let logging = LoggingMiddleware::default();
+    w.exchange.middlewares.push(Arc::new(logging.clone()));
+    w.logging = Some(logging);
+}
+
+#[given(regex = r"^a middleware adding (\S+)=(\S+) to every request$")]
+async fn add_param_middleware(w: &mut ExchangeWorld, key: String, value: String) {
+    w.exchange.middlewares.push(Arc::new(AddParam { key, value }));
+}
+
+#[then(expr = "placing an order should fail with HTTP status {int}")]
+async fn validate_add_order_status(w: &mut ExchangeWorld, status: u16) {
+    let order = NewOrder::new("XBTUSD", Side::Buy, OrderType::Limit, Decimal::new(125, 2)).price(Decimal::new(37500, 0));
+    match w.exchange.add_order(order).await {
+        Err(ExchangeError::Status(s)) => assert_eq!(s.as_u16(), status),
+        other => panic!("Expected HTTP status {}, got {:?}", status, other),
+    }
+}
+
+fn sent_bodies(w: &ExchangeWorld, path: &str) -> Vec<String> {
+    let url = format!("https://api.example.com{}",path);
+    w.memory_transport
+        .as_ref()
+        .expect("In-memory transport missing.")
+        .requests()
+        .into_iter()
+        .filter(|r| r.method == "POST" && r.url == url)
+        .map(|r| r.body.expect("POST request has no body."))
+        .collect()
+}
+
+#[then(expr = "the POST requests to {word} should all have different nonces")]
+async fn validate_distinct_nonces(w: &mut ExchangeWorld, path: String) {
+    let mut nonces: Vec<String> = sent_bodies(w, &path)
+        .iter()
+        .map(|body| body.split('&').find_map(|p| p.strip_prefix("nonce=")).expect("Missing nonce.").to_string())
+        .collect();
+    let sent = nonces.len();
+    nonces.sort();
+    nonces.dedup();
+    assert_eq!(nonces.len(), sent);
+}
+
+#[then(regex = r"^the (last )?POST request to (\S+) should contain (\S+)$")]
+async fn validate_sent_param(w: &mut ExchangeWorld, last: String, path: String, param: String) {
+    let mut bodies = sent_bodies(w, &path);
+    if !last.is_empty() {
+        bodies = bodies.split_off(bodies.len().saturating_sub(1));
+    }
+    for body in bodies {
+        assert!(body.split('&').any(|p| p == param), "{} missing from {}", param, body);
+    }
+}
+
+#[then(expr = "no POST request to {word} should contain a {word} parameter")]
+async fn validate_param_not_sent(w: &mut ExchangeWorld, path: String, key: String) {
+    let prefix = format!("{}=",key);
+    for body in sent_bodies(w, &path) {
+        assert!(!body.split('&').any(|p| p.starts_with(&prefix)), "{} sent in {}", key, body);
+    }
+}
+
+#[then(expr = "the last GET request to {word} should have the query {word}")]
+async fn validate_sent_query(w: &mut ExchangeWorld, path: String, query: String) {
+    let requests = w.memory_transport.as_ref().expect("In-memory transport missing.").requests();
+    let url = format!("https://api.example.com{}",path);
+    let request = requests
+        .iter()
+        .rev()
+        .find(|r| r.method == "GET" && r.url.split('?').next() == Some(url.as_str()))
+        .expect("No GET request sent.");
+    assert_eq!(request.url.split_once('?').map(|(_, q)| q), Some(query.as_str()));
+}
+
+#[then(expr = "the metrics should report {int} calls and {int} errors for {word}")]
+async fn validate_metrics(w: &mut ExchangeWorld, calls: u64, errors: u64, href: String) {
+    let snapshot = w.metrics.as_ref().expect("Metrics middleware missing.").snapshot();
+    let metrics = snapshot.get(&href).expect("No metrics for endpoint.");
+    assert_eq!(metrics.calls, calls);
+    assert_eq!(metrics.errors, errors);
+    assert!(metrics.max >= metrics.mean());
+}
+
+#[then(expr = "the logged parameters for {word} should be {word}")]
+async fn validate_logged_params(w: &mut ExchangeWorld, params: String, logged: String) {
+    let logging = w.logging.as_ref().expect("Logging middleware missing.");
+    let payload: Payload = params
+        .split(',')
+        .map(|p| p.split_once('=').expect("Invalid parameter."))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    assert_eq!(logging.params(&payload), logged);
+}
+
+#[given("a local HTTP server")]
+async fn start_local_server(w: &mut ExchangeWorld) {
+    w.local_server = Some(LocalServer::start(true));
+}
+
+#[given("a local HTTP server that never answers")]
+async fn start_silent_local_server(w: &mut ExchangeWorld) {
+    w.local_server = Some(LocalServer::start(false));
+}
+
+// Builds from a table of setting | value rows. The value `local server` is
+// replaced with the local server's URL.
+fn builder_from_table(w: &ExchangeWorld, step: &Step) -> BaseExchangeBuilder {
+    let table = step.table().expect("Settings table missing.");
+    let mut builder = BaseExchange::builder();
+    for row in &table.rows {
+        let value = match (row[1].as_str(), &w.local_server) {
+            ("local server", Some(server)) => server.url.clone(),
+            ("local server", None) => panic!("Local server missing."),
+            (value, _) => value.to_string(),
+        };
+        let secs = || Duration::from_secs(value.parse().expect("Invalid number of seconds."));
+        builder = match row[0].as_str() {
+            "base_url" => builder.base_url(&format!("{}/0",value)),
+            "connect_timeout" => builder.connect_timeout(secs()),
+            "request_timeout" => builder.request_timeout(secs()),
+            "pool_max_idle_per_host" => builder.pool_max_idle_per_host(value.parse().expect("Invalid pool size.")),
+            "pool_idle_timeout" => builder.pool_idle_timeout(secs()),
+            "proxy" => builder.proxy(&value),
+            "user_agent" => builder.user_agent(&value),
+            "tcp_keepalive" => builder.tcp_keepalive(secs()),
+            "http2_prior_knowledge" if value == "true" => builder.http2_prior_knowledge(),
+            setting => panic!("Unknown client setting {}.", setting),
+        };
+    }
+    builder
+}
+
+#[given("an exchange built with")]
+async fn build_exchange(w: &mut ExchangeWorld, #[step] step: &Step) {
+    w.exchange = builder_from_table(w, step).build().expect("Error building exchange.");
+}
+
+#[then("building an exchange with these settings should fail")]
+async fn validate_build_failure(w: &mut ExchangeWorld, #[step] step: &Step) {
+    match builder_from_table(w, step).build() {
+        Err(ExchangeError::Config(_)) => (),
+        other => panic!("Expected a configuration error, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[given(regex = r"^(\d+) exchanges for the local server (sharing one client|with their own clients)$")]
+async fn build_local_exchanges(w: &mut ExchangeWorld, count: usize, clients: String) {
+    let base_url = format!("{}/0",w.local_server.as_ref().expect("Local server missing.").url);
+    let shared = HttpConfig::default().build_client().expect("Error building HTTP client.");
+    w.exchanges = (0..count)
+        .map(|_| {
+            let builder = BaseExchange::builder().base_url(&base_url);
+            match clients.as_str() {
+                "sharing one client" => builder.client(shared.clone()),
+                _ => builder,
+            }
+            .build()
+            .expect("Error building exchange.")
+        })
+        .collect();
+}
+
+#[when("each exchange requests the server time")]
+async fn request_server_time_each(w: &mut ExchangeWorld) {
+    for exchange in w.exchanges.iter_mut() {
+        let server_time = exchange.get_server_time().await.expect("Error requesting server time.");
+        assert_eq!(server_time.unixtime, 1700000000);
+    }
+}
+
+#[then(expr = "requesting the server time should return {int}")]
+async fn validate_server_time(w: &mut ExchangeWorld, unixtime: i64) {
+    let server_time = w.exchange.get_server_time().await.expect("Error requesting server time.");
+    assert_eq!(server_time.unixtime, unixtime);
+}
+
+#[then(expr = "requesting the server time should time out within {int} seconds")]
+async fn validate_server_time_timeout(w: &mut ExchangeWorld, secs: u64) {
+    let started = Instant::now();
+    match w.exchange.get_server_time().await {
+        Err(ExchangeError::Request(_)) => assert!(started.elapsed() < Duration::from_secs(secs)),
+        other => panic!("Expected the request to time out, got {:?}", other),
+    }
+}
+
+#[then(expr = "the local server should have accepted {int} connection(s) for {int} request(s)")]
+async fn validate_local_connections(w: &mut ExchangeWorld, connections: usize, requests: usize) {
+    let server = w.local_server.as_ref().expect("Local server missing.");
+    assert_eq!(server.connections.load(Ordering::SeqCst), connections);
+    assert_eq!(server.requests.lock().unwrap().len(), requests);
+}
+
+#[then(expr = "the local server should have received {string} from {string}")]
+async fn validate_local_request(w: &mut ExchangeWorld, request_line: String, user_agent: String) {
+    let server = w.local_server.as_ref().expect("Local server missing.");
+    let requests = server.requests.lock().unwrap();
+    assert!(requests.contains(&(request_line, user_agent)), "Requests received: {:?}", requests);
+}
+
+fn account_tier(tier: &str) -> AccountTier {
+    match tier {
+        "Starter" => AccountTier::Starter,
+        "Intermediate" => AccountTier::Intermediate,
+        _ => AccountTier::Pro,
+    }
+}
+
+#[given(regex = r"^an? (Starter|Intermediate|Pro) tier rate limiter that rejects calls$")]
+async fn create_rate_limiter(w: &mut ExchangeWorld, tier: String) {
+    w.rate_limiter = Some(RateLimiter::new(account_tier(&tier), RateLimitMode::Reject));
+}
+
+#[given(regex = r"^the exchange has an? (Starter|Intermediate|Pro) tier rate limiter that rejects calls$")]
+async fn add_exchange_rate_limiter(w: &mut ExchangeWorld, tier: String) {
+    let limiter = RateLimiter::new(account_tier(&tier), RateLimitMode::Reject);
+    w.exchange.rate_limiter = Some(Arc::new(Mutex::new(limiter)));
+}
+
+#[when(regex = r"^(\d+) tasks each request open orders (through one shared exchange|from their own clone of the exchange)$")]
+async fn request_open_orders_concurrently(w: &mut ExchangeWorld, tasks: usize, sharing: String) {
+    let shared = Arc::new(w.exchange.clone());
+    let handles: Vec<_> = (0..tasks)
+        .map(|_| {
+            let exchange = match sharing.as_str() {
+                "through one shared exchange" => shared.clone(),
+                _ => Arc::new(w.exchange.clone()),
+            };
+            tokio::spawn(async move { exchange.get_open_orders().await })
+        })
+        .collect();
+    w.accepted_calls = 0;
+    for handle in handles {
+        match handle.await.expect("Task panicked.") {
+            Ok(_) => w.accepted_calls += 1,
+            Err(ExchangeError::RateLimited(_)) => (),
+            Err(e) => panic!("Unexpected error requesting open orders: {}", e),
+        }
+    }
+}
+
+#[when(regex = r"^(\d+) (\S+) calls are made(?: for (\S+))?$")]
+async fn make_rate_limited_calls(w: &mut ExchangeWorld, calls: usize, endpoint: String, pair: String) {
+    let rate_limiter = w.rate_limiter.as_mut().expect("Rate limiter missing.");
+    let pair = match pair.is_empty() {
+        true => None,
+        false => Some(pair.as_str()),
+    };
+    w.accepted_calls = (0..calls)
+        .filter(|_| rate_limiter.reserve(&endpoint, pair).is_ok())
+        .count();
+}
+
+#[then(expr = "{int} calls should be accepted and the rest rejected")]
+async fn validate_accepted_calls(w: &mut ExchangeWorld, accepted: usize) {
+    assert_eq!(w.accepted_calls, accepted);
+}
+
+#[when(expr = "order {word} is canceled")]
+async fn cancel_order(w: &mut ExchangeWorld, txid: String) {
+    w.exchange.cancel_order(&txid).await.expect("Error canceling order.");
+}
+
+#[then(expr = "the order-rate headroom for {word} should be {int}")]
+async fn validate_order_rate_headroom(w: &mut ExchangeWorld, pair: String, headroom: i64) {
+    let order_headroom = w.exchange.order_rate_headroom(&pair).await.expect("Rate limiter missing.");
+    assert_eq!(order_headroom.remaining.round() as i64, headroom);
+}
+
+#[then(expr = "the API counter headroom should be {int}")]
+async fn validate_api_counter_headroom(w: &mut ExchangeWorld, headroom: i64) {
+    let rate_limiter = w.rate_limiter.as_mut().expect("Rate limiter missing.");
+    assert_eq!(rate_limiter.headroom().remaining.round() as i64, headroom);
+}
+
+#[given(regex = r"an in-process nonce provider with (Millis|Micros|Nanos) resolution")]
+async fn create_in_process_nonce(w: &mut ExchangeWorld, resolution: String) {
+    let resolution = match resolution.as_str() {
+        "Micros" => NonceResolution::Micros,
+        "Nanos" => NonceResolution::Nanos,
+        _ => NonceResolution::Millis,
+    };
+    w.nonce_providers = vec![Arc::new(IncreasingNonce::new(resolution))];
+}
+
+#[given(expr = "{int} file nonce providers sharing one file")]
+async fn create_file_nonces(w: &mut ExchangeWorld, providers: usize) {
+    let nonce_file = std::env::temp_dir().join(format!("exchange_interface_nonce_{}", process::id()));
+    let _ = fs::remove_file(&nonce_file);
+    w.nonce_providers = (0..providers)
+        .map(|_| Arc::new(FileNonce::new(&nonce_file, NonceResolution::Millis)) as Arc<dyn NonceProvider>)
+        .collect();
+    w.nonce_file = Some(nonce_file);
+}
+
+#[when(expr = "{int} threads each take {int} nonces")]
+async fn take_nonces(w: &mut ExchangeWorld, threads: usize, nonces: usize) {
+    let handles: Vec<thread::JoinHandle<Vec<u64>>> = (0..threads)
+        .map(|i| {
+            let provider = w.nonce_providers[i % w.nonce_providers.len()].clone();
+            thread::spawn(move || {
+                (0..nonces).map(|_| provider.next_nonce().expect("Error creating nonce.")).collect()
+            })
+        })
+        .collect();
+    w.issued_nonces = handles.into_iter().map(|h| h.join().expect("Nonce thread panicked.")).collect();
+}
+
+#[then("every nonce should be unique and increasing within each thread")]
+async fn validate_nonces(w: &mut ExchangeWorld) {
+    for issued in &w.issued_nonces {
+        if issued.windows(2).any(|pair| pair[0] >= pair[1]) {
+            panic!("Nonces are not strictly increasing.");
+        }
+    }
+    let mut all: Vec<u64> = w.issued_nonces.concat();
+    let total = all.len();
+    all.sort_unstable();
+    all.dedup();
+    assert_eq!(all.len(), total);
+}
+
+#[then("the nonce file should hold the largest nonce issued")]
+async fn validate_nonce_file(w: &mut ExchangeWorld) {
+    let nonce_file = w.nonce_file.as_ref().expect("Nonce file missing.");
+    let stored: u64 = read_to_string(nonce_file).expect("Error reading nonce file.").parse().expect("Invalid nonce file.");
+    let _ = fs::remove_file(nonce_file);
+    assert_eq!(Some(stored), w.issued_nonces.concat().into_iter().max());
+}
+
+#[given(regex = r"^a TOTP provider with secret (.+) and (\d+) digits$")]
+async fn create_totp(w: &mut ExchangeWorld, secret: String, digits: u32) {
+    let totp = Totp::from_base32(&secret).expect("Invalid TOTP secret.").digits(digits).expect("Invalid TOTP digits.");
+    w.exchange.otp = Some(Arc::new(totp));
+}
+
+#[then(regex = r"^a TOTP provider with (\d+) digits and a (\d+) second step should be rejected$")]
+async fn validate_totp_rejected(_w: &mut ExchangeWorld, digits: u32, seconds: u64) {
+    match Totp::new(SecretKey::new(b"12345678901234567890".to_vec()), digits, Duration::from_secs(seconds)) {
+        Err(ExchangeError::Otp(_)) => (),
+        other => panic!("Expected an OTP error, got {:?}", other),
+    }
+}
+
+#[then(expr = "the code at {int} should be {word}")]
+async fn validate_totp_code(w: &mut ExchangeWorld, time: u64, code: String) {
+    let otp = w.exchange.otp.as_ref().expect("OTP provider missing.");
+    assert_eq!(otp.code(UNIX_EPOCH + Duration::from_secs(time)).expect("Error creating OTP."), code);
+}
+
+#[given(regex = r"^an exchange instance requiring 2FA signing with (\S+) and (TOTP secret|static password) (\S+)$")]
+async fn create_2fa_signing_exchange(w: &mut ExchangeWorld, secret: String, kind: String, otp: String) {
+    w.exchange = shared_exchange();
+    w.exchange.api_key_2fa = "API_KEY_2FA".to_string();
+    w.exchange.api_secret_2fa = SecretKey::from_base64(&secret).expect("Invalid secret.");
+    w.exchange.api_passphrase_required = Some(true);
+    w.exchange.otp = match kind.as_str() {
+        "TOTP secret" => Some(Arc::new(Totp::from_base32(&otp).expect("Invalid TOTP secret."))),
+        _ => Some(Arc::new(StaticOtp::new(&otp))),
+    };
+}
+
+#[then(expr = "the sent body should contain a {int} digit otp")]
+async fn validate_sent_otp(w: &mut ExchangeWorld, digits: usize) {
+    let signed_request = w.signed_request.as_ref().unwrap();
+    let body = signed_request.prepared.body.as_ref().expect("Prepared private request has no body.");
+    let otp = body
+        .split('&')
+        .find_map(|param| param.strip_prefix("otp="))
+        .expect("otp missing from the sent body.");
+    assert_eq!(otp.len(), digits);
+    assert!(otp.chars().all(|c| c.is_ascii_digit()));
+}
+
+#[then(regex = r"^the debug output should (not contain|contain) (\S+)$")]
+async fn validate_debug_output(w: &mut ExchangeWorld, context: String, value: String) {
+    let debug_output = format!("{:?}",w);
+    match context.as_str() {
+        "contain" => assert!(debug_output.contains(&value)),
+        _ => assert!(!debug_output.contains(&value), "Debug output leaked {}.",value),
+    }
+}
+
+#[then("the debug output should not contain the API-Sign header")]
+async fn validate_debug_output_api_sign(w: &mut ExchangeWorld) {
+    let debug_output = format!("{:?}",w);
+    let signed_request = w.signed_request.as_ref().expect("No request prepared.");
+    assert!(!debug_output.contains(signed_request.api_sign()), "Debug output leaked the API-Sign header.");
+}
+
+#[then("the debug output should not contain the decoded secret bytes")]
+async fn validate_debug_output_bytes(w: &mut ExchangeWorld) {
+    let debug_output = format!("{:?}",w);
+    let decoded = format!("{:?}",w.exchange.api_secret_2fa.expose());
+    assert!(!debug_output.contains(decoded.trim_start_matches('[').trim_end_matches(']')));
+}
+
+#[then(expr = "loading the secret {word} should fail")]
+async fn validate_invalid_secret(_w: &mut ExchangeWorld, secret: String) {
+    assert!(SecretKey::from_base64(&secret).is_err());
+}
+
+#[given(regex = r"^a (toml|yaml) configuration$")]
+async fn create_config(w: &mut ExchangeWorld, format: String, #[step] step: &Step) {
+    let contents = step.docstring().expect("Configuration docstring missing.");
+    let config = match format.as_str() {
+        "toml" => Config::from_toml_str(contents),
+        _ => Config::from_yaml_str(contents),
+    };
+    w.config = Some(config.expect("Invalid configuration."));
+}
+
+#[given(expr = "the environment variable {word} is {word}")]
+async fn override_config(w: &mut ExchangeWorld, key: String, value: String) {
+    let config = w.config.as_mut().expect("Configuration missing.");
+    config.apply_env(vec![(key, value)]).expect("Invalid configuration override.");
+}
+
+#[when(expr = "the {word} profile is loaded")]
+async fn load_profile(w: &mut ExchangeWorld, name: String) {
+    let config = w.config.as_ref().expect("Configuration missing.");
+    let name = match name.as_str() {
+        "default" => None,
+        _ => Some(name.as_str()),
+    };
+    let profile = config.profile(name).expect("Error loading profile.");
+    w.exchange = BaseExchange::from_profile(profile).expect("Error creating exchange from profile.");
+}
+
+#[then(regex = r"^the exchange should use API key (\S+) (with 2FA required|without 2FA)$")]
+async fn validate_profile_keys(w: &mut ExchangeWorld, api_key: String, context: String) {
+    let exchange = &mut w.exchange;
+    match context.as_str() {
+        "without 2FA" => {
+            assert!(!exchange.api_pass_required());
+            assert_eq!(exchange.api_key, api_key);
+            assert!(!exchange.api_secret.is_empty());
+        },
+        _ => {
+            assert!(exchange.api_pass_required());
+            assert!(exchange.otp.is_some());
+            assert_eq!(exchange.api_key_2fa, api_key);
+            assert!(!exchange.api_secret_2fa.is_empty());
+        },
+    }
+}
+
+#[then(expr = "the exchange should use base url {word}")]
+async fn validate_profile_base_url(w: &mut ExchangeWorld, base_url: String) {
+    assert_eq!(w.exchange.base_url, base_url);
+}
+
+#[then(regex = r"^the exchange should have an? (starter|intermediate|pro) tier rate limiter$")]
+async fn validate_profile_rate_limiter(w: &mut ExchangeWorld, tier: String) {
+    let rate_limiter = w.exchange.rate_limiter.as_ref().expect("Rate limiter missing.");
+    assert_eq!(format!("{:?}",rate_limiter.lock().unwrap().tier).to_lowercase(), tier);
+}
+
+#[then("the exchange should not have a rate limiter")]
+async fn validate_profile_no_rate_limiter(w: &mut ExchangeWorld) {
+    assert!(w.exchange.rate_limiter.is_none());
+}
+
+#[then(expr = "the {word} profile should use base url {word}")]
+async fn validate_named_profile_base_url(w: &mut ExchangeWorld, name: String, base_url: String) {
+    let config = w.config.as_ref().expect("Configuration missing.");
+    assert_eq!(config.profile(Some(&name)).expect("Error loading profile.").base_url, base_url);
+}
+
+#[then(expr = "loading the {word} profile should fail")]
+async fn validate_missing_profile(w: &mut ExchangeWorld, name: String) {
+    let config = w.config.as_ref().expect("Configuration missing.");
+    assert!(config.profile(Some(&name)).is_err());
+}
+
+// Scenarios run concurrently, so each vault gets its own file.
+static VAULT_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+// The minimum Argon2 cost keeps the scenarios fast.
+const TEST_KDF: KdfParams = KdfParams {
+    m_cost: 8,
+    t_cost: 1,
+    p_cost: 1,
+};
+
+#[given(expr = "a new vault with passphrase {word}")]
+async fn create_vault(w: &mut ExchangeWorld, passphrase: String) {
+    let vault_file = std::env::temp_dir().join(format!(
+        "exchange_interface_vault_{}_{}.json",
+        process::id(),
+        VAULT_COUNT.fetch_add(1, Ordering::SeqCst),
+    ));
+    let _ = fs::remove_file(&vault_file);
+    let vault = Vault::create_with_params(&vault_file, &SecretString::new(&passphrase), TEST_KDF);
+    w.vault = Some(vault.expect("Error creating vault."));
+    w.vault_file = Some(vault_file);
+}
+
+#[given(expr = "the vault profile {word} with API key {word} and secret {word}")]
+async fn add_vault_profile(w: &mut ExchangeWorld, name: String, api_key: String, api_secret: String) {
+    let vault = w.vault.as_mut().expect("Vault missing.");
+    let profile = Profile {
+        base_url: "https://api.example.com/0".to_string(),
+        api_key,
+        api_secret: SecretString::new(&api_secret),
+        ..Profile::default()
+    };
+    vault.add(&name, profile).expect("Error adding vault profile.");
+}
+
+#[when("the vault is saved")]
+async fn save_vault(w: &mut ExchangeWorld) {
+    w.vault.as_ref().expect("Vault missing.").save().expect("Error saving vault.");
+}
+
+#[when(expr = "the {word} profile is rotated to API key {word} and secret {word}")]
+async fn rotate_vault_profile(w: &mut ExchangeWorld, name: String, api_key: String, api_secret: String) {
+    let vault = w.vault.as_mut().expect("Vault missing.");
+    vault.rotate(&name, &api_key, SecretString::new(&api_secret)).expect("Error rotating vault profile.");
+}
+
+#[when(expr = "the {word} profile is removed from the vault")]
+async fn remove_vault_profile(w: &mut ExchangeWorld, name: String) {
+    w.vault.as_mut().expect("Vault missing.").remove(&name).expect("Error removing vault profile.");
+}
+
+#[then(expr = "the vault opened with passphrase {word} should list {word}")]
+async fn validate_vault_names(w: &mut ExchangeWorld, passphrase: String, names: String) {
+    let vault_file = w.vault_file.as_ref().expect("Vault file missing.");
+    let vault = Vault::open(vault_file, &SecretString::new(&passphrase)).expect("Error opening vault.");
+    assert_eq!(vault.names().cloned().collect::<Vec<String>>().join(","), names);
+}
+
+#[then(expr = "the vault file should not contain the secret {word}")]
+async fn validate_vault_ciphertext(w: &mut ExchangeWorld, api_secret: String) {
+    let vault_file = w.vault_file.as_ref().expect("Vault file missing.");
+    let contents = read_to_string(vault_file).expect("Error reading vault file.");
+    assert!(!contents.contains(&api_secret));
+    assert!(!contents.contains("main-key"));
+}
+
+#[when(expr = "the exchange loads the {word} profile from the vault with passphrase {word}")]
+async fn load_vault_profile(w: &mut ExchangeWorld, name: String, passphrase: String) {
+    let vault_file = w.vault_file.as_ref().expect("Vault file missing.");
+    let exchange = BaseExchange::from_vault(vault_file, &SecretString::new(&passphrase), &name);
+    w.exchange = exchange.expect("Error loading exchange from vault.");
+}
+
+#[then(expr = "opening the vault with passphrase {word} should fail")]
+async fn validate_wrong_passphrase(w: &mut ExchangeWorld, passphrase: String) {
+    let vault_file = w.vault_file.as_ref().expect("Vault file missing.");
+    match Vault::open(vault_file, &SecretString::new(&passphrase)) {
+        Err(ExchangeError::Vault(_)) => {},
+        other => panic!("Expected a vault error, got {:?}", other),
+    }
+}
+
+#[then(expr = "loading the {word} profile from the vault with passphrase {word} should fail")]
+async fn validate_missing_vault_profile(w: &mut ExchangeWorld, name: String, passphrase: String) {
+    let vault_file = w.vault_file.as_ref().expect("Vault file missing.");
+    assert!(BaseExchange::from_vault(vault_file, &SecretString::new(&passphrase), &name).is_err());
+    let _ = fs::remove_file(vault_file);
+}
+
 #[given(regex = "a populated exchange instance (requires|not requiring) API_PASSPHRASE")]
 async fn verify_existing_exchange(w: &mut ExchangeWorld, context: String) {
-    let ctx_required = context == String::from("required");
+    let ctx_required = context == "required";
     let api_pass_required = w.exchange.api_pass_required();
-    if ctx_required && api_pass_required && w.exchange.api_passphrase.is_empty() {
+    if ctx_required && api_pass_required && w.exchange.otp.is_none() {
         panic!("Error API_PASSPHRASE environment variable missing.")
     }
 
     let exchange = &mut w.exchange;
-    let api_passphrase = &exchange.api_passphrase;
     
-    let mut API_KEY_VAR = "API_KEY".to_string();
-    let mut API_SECRET_VAR = "API_SECRET".to_string();
+    let mut api_key_var = "API_KEY".to_string();
+    let mut api_secret_var = "API_SECRET".to_string();
     
     if ctx_required {
-        API_KEY_VAR.push_str("_2FA");
-        API_SECRET_VAR.push_str("_2FA");
+        api_key_var.push_str("_2FA");
+        api_secret_var.push_str("_2FA");
     }
 
-    let BASE_URL = match var("BASE_URL") {
-        Ok(val) => val,
-        Err(_) => "".to_string(),
-    };
-    let API_KEY = match var(API_KEY_VAR) {
+    let env_base_url = match var("BASE_URL") {
         Ok(val) => val,
         Err(_) => "".to_string(),
     };
-    let API_SECRET = match var(API_SECRET_VAR) {
+    let env_api_key = match var(api_key_var) {
         Ok(val) => val,
         Err(_) => "".to_string(),
     };
-    if exchange.base_url != BASE_URL {
+    let env_api_secret = secret_from_env(&api_secret_var);
+    if exchange.base_url != env_base_url {
         panic!("Invalid BASE_URL value on exchange.");
     } else if exchange.base_url.is_empty() {
         panic!("Error BASE_URL is a required for an exchange instance.")
     }
     if ctx_required {
-        let API_PASSPHRASE = match var("API_PASSPHRASE") {
-            Ok(val) => val,
-            Err(_) => panic!("API_PASSPHRASE is required for this exchange instance.")
+        let env_otp = match otp_from_env() {
+            Some(val) => val,
+            None => panic!("API_PASSPHRASE or API_TOTP_SECRET is required for this exchange instance.")
         };
-        if exchange.api_key_2fa != API_KEY {
+        if exchange.api_key_2fa != env_api_key {
             panic!("Invalid API_KEY value on exchange.");
-        } else if exchange.api_secret_2fa != API_SECRET {
+        } else if exchange.api_secret_2fa != env_api_secret {
             panic!("Invalid API_SECRET value on exchange.");
-        } else {
-            match api_passphrase.is_empty() {
-                true => {
-                    exchange.api_passphrase = API_PASSPHRASE;
-                },
-                false => {
-                    if api_passphrase != &API_PASSPHRASE {
-                        panic!("Error API_PASSPHRASE mismatch.");
-                    }
-                }
-            }
+        } else if exchange.otp.is_none() {
+            exchange.otp = Some(env_otp);
         }
     } else {
-        if exchange.api_key != API_KEY {
+        if exchange.api_key != env_api_key {
             panic!("Invalid API_KEY value on exchange.");
-        } else if exchange.api_secret != API_SECRET {
+        } else if exchange.api_secret != env_api_secret {
             panic!("Invalid API_SECRET value on exchange.");
         }
     }
@@ -386,14 +2087,14 @@ async fn open_orders_request_sent_response_received(w: &mut ExchangeWorld, error
     let response = exchange.get_open_orders().await;
     match response {
         Ok(res) => {
-            if errors != res.error.len() {
-                panic!("Errors received while requesting orders");
+            if errors != 0 {
+                panic!("Expected {} errors while requesting orders, received none.",errors);
             }
-            match res.result {
-                ResponseResult::OpenOrdersResponse(r) => {
-                    w.open_orders = Some(r);
-                },
-                _ => panic!("Invalid ResponseResult received.")
+            w.open_orders = Some(res);
+        },
+        Err(ExchangeError::Api(errs)) => {
+            if errors != errs.len() {
+                panic!("Errors received while requesting orders: {}",errs.join(", "));
             }
         },
         Err(e) => {
@@ -405,13 +2106,16 @@ async fn open_orders_request_sent_response_received(w: &mut ExchangeWorld, error
 #[then(expr = "the response should contain the OpenOrders result")]
 async fn validate_open_orders_response(w: &mut ExchangeWorld) {
     let open_orders = &w.open_orders;
-    if let None = open_orders {
+    if open_orders.is_none() {
         panic!("Invalid Open Orders object received.")
     }
 }
 
 #[tokio::main]
 async fn main() {
+    // Credentials are read at runtime; a local .env is optional.
+    dotenv::dotenv().ok();
+
     let mut public_features_path = String::from("/public_features");
     let mut private_features_path = String::from("/private_features");
     if !Path::new(&public_features_path).exists() {
@@ -445,4 +2149,4 @@ fn format_json_file(filename: &str) {
         },
         Err(_) => panic!("Error reading file to string.")
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,53 @@
+Feature: Blocking client
+    Background:
+        Given an exchange instance with an in-memory transport signing with aWprbG1ub3A=
+
+    Scenario: Public endpoints answer without an async runtime
+        Given the transport answers /0/public/Time with
+            """
+            {"error": [], "result": {"unixtime": 1700000000, "rfc1123": "Tue, 14 Nov 23 22:13:20 +0000"}}
+            """
+        Then the blocking client should read the server time 1700000000
+        And the transport should have received 1 GET request to /0/public/Time
+
+    Scenario: Private endpoints are signed and decoded into the same models
+        Given the transport answers /0/private/OpenOrders with
+            """
+            {"error": [], "result": {"open": {"OQCLML-BW3P3-BUCMWZ": {"status": "open", "opentm": 1688666559.8974, "descr": {"pair": "XBTUSD", "type": "buy", "ordertype": "limit", "price": "37500.0", "price2": "0", "leverage": "none", "order": "buy 1.25000000 XBTUSD @ limit 37500.0"}, "vol": "1.25000000", "vol_exec": "0.00000000", "cost": "0.00000", "fee": "0.00000", "price": "0.00000", "misc": "", "oflags": "fciq"}}}}
+            """
+        When the blocking client requests open orders
+        Then order OQCLML-BW3P3-BUCMWZ should be an open buy limit order
+        And the transport should have received 1 POST request to /0/private/OpenOrders with a valid API-Sign
+
+    Scenario: Orders are placed through the blocking client
+        Given the transport answers /0/private/AddOrder with
+            """
+            {"error": [], "result": {"descr": {"order": "buy 1.25000000 XBTUSD @ limit 37500.0"}, "txid": ["OUF4EM-FRGI2-MQMWZD"]}}
+            """
+        When the blocking client places an order
+        Then the order should have been placed as OUF4EM-FRGI2-MQMWZD
+        And the transport should have received 1 POST request to /0/private/AddOrder with a valid API-Sign
+
+    Scenario: Errors are the same as with the async client
+        Given the transport answers /0/private/OpenOrders with HTTP status 502
+        Then the blocking client should fail to request open orders with HTTP status 502
+
+    Scenario: Orders are looked up by userref through the blocking client
+        Given the transport answers /0/private/OpenOrders with
+            """
+            {"error": [], "result": {"open": {
+                "OLDORD-BW3P3-BUCMWZ": {"userref": 7, "opentm": 1688666559.8974, "status": "open", "descr": {"order": "buy 2.00000000 XBTUSD @ limit 30000.0"}},
+                "OQCLML-BW3P3-BUCMWZ": {"userref": 7, "opentm": 1700000001.0, "status": "open", "descr": {"order": "buy 1.25000000 XBTUSD @ limit 37500.0"}}
+            }}}
+            """
+        Then the blocking client should find order OQCLML-BW3P3-BUCMWZ by userref 7 since 1700000000
+        And the POST request to /0/private/OpenOrders should contain userref=7
+
+    Scenario: Order-rate headroom is reported by the blocking client
+        Given the exchange has a Starter tier rate limiter that rejects calls
+        And the transport answers /0/private/AddOrder with
+            """
+            {"error": [], "result": {"descr": {"order": "buy 1.25000000 XBTUSD @ limit 37500.0"}, "txid": ["OUF4EM-FRGI2-MQMWZD"]}}
+            """
+        When the blocking client places an order
+        Then the blocking client should report an order-rate headroom of 59 for XBTUSD
@@ -0,0 +1,23 @@
+Feature: Sharing an exchange between tasks
+    Scenario: Tasks sharing one exchange send signed requests with distinct nonces
+        Given an exchange instance with an in-memory transport signing with kQH5HW/8p1uGOVjbgWA7FunAmGO8lsSUXNsu3eow76sz84Q18fWxnyRzBHCd3pd5nE9qa99HAZtuZuj6F1huXg==
+        And the transport answers /0/private/OpenOrders with
+            """
+            {"error": [], "result": {"open": {}}}
+            """
+        When 25 tasks each request open orders through one shared exchange
+        Then 25 calls should be accepted and the rest rejected
+        And the transport should have received 25 POST requests to /0/private/OpenOrders with a valid API-Sign
+        And the POST requests to /0/private/OpenOrders should all have different nonces
+
+    Scenario: Clones of an exchange share its rate limiter and nonces
+        Given an exchange instance with an in-memory transport signing with aWprbG1ub3A=
+        And the exchange has a Starter tier rate limiter that rejects calls
+        And the transport answers /0/private/OpenOrders with
+            """
+            {"error": [], "result": {"open": {}}}
+            """
+        When 20 tasks each request open orders from their own clone of the exchange
+        Then 15 calls should be accepted and the rest rejected
+        And the transport should have received 15 POST requests to /0/private/OpenOrders with a valid API-Sign
+        And the POST requests to /0/private/OpenOrders should all have different nonces
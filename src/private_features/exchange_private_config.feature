@@ -0,0 +1,66 @@
+Feature: Runtime configuration profiles
+    Scenario: Profiles are read from TOML and overridden from the environment
+        Given a toml configuration
+            """
+            default_profile = "main"
+
+            [profiles.main]
+            base_url = "https://api.example.com/0"
+            api_key = "main-key"
+            api_secret = "kQH5HW/8p1uGOVjbgWA7FunAmGO8lsSUXNsu3eow76sz84Q18fWxnyRzBHCd3pd5nE9qa99HAZtuZuj6F1huXg=="
+            rate_tier = "intermediate"
+
+            [profiles.main.otp]
+            method = "totp"
+            secret = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ"
+
+            [profiles.main.timeouts]
+            connect = 5
+            request = 30
+
+            [profiles.readonly]
+            base_url = "https://api.example.com/0"
+            api_key = "readonly-key"
+            api_secret = "aWprbG1ub3A="
+            """
+        And the environment variable EXCHANGE_MAIN_API_KEY is override-key
+        And the environment variable EXCHANGE_BACKUP_BASE_URL is https://backup.example.com/0
+        When the default profile is loaded
+        Then the exchange should use API key override-key with 2FA required
+        And the exchange should use base url https://api.example.com/0
+        And the exchange should have a intermediate tier rate limiter
+        When the readonly profile is loaded
+        Then the exchange should use API key readonly-key without 2FA
+        And the exchange should not have a rate limiter
+        And the backup profile should use base url https://backup.example.com/0
+
+    Scenario: Profiles are read from YAML
+        Given a yaml configuration
+            """
+            profiles:
+              bot:
+                base_url: https://api.example.com/0
+                api_key: bot-key
+                api_secret: aWprbG1ub3A=
+                otp:
+                  method: static
+                  password: hunter2
+                rate_tier: pro
+                rate_limit_mode: reject
+                venue: example
+                ws_public_url: wss://ws.example.com/v2
+                ws_private_url: wss://ws-auth.example.com/v2
+            """
+        When the bot profile is loaded
+        Then the exchange should use API key bot-key with 2FA required
+        And the exchange should have a pro tier rate limiter
+        And the unified venue should be example
+        And the exchange's WebSocket URLs should be wss://ws.example.com/v2 and wss://ws-auth.example.com/v2
+
+    Scenario: Loading an unknown profile fails
+        Given a toml configuration
+            """
+            [profiles.main]
+            base_url = "https://api.example.com/0"
+            """
+        Then loading the missing profile should fail
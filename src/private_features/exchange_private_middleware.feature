@@ -0,0 +1,66 @@
+Feature: Request middleware
+    Scenario: Transient failures of idempotent calls are retried
+        Given an exchange instance with an in-memory transport signing with aWprbG1ub3A=
+        And a retry middleware with 3 attempts
+        And the transport answers /0/public/Time with HTTP status 502
+        And the transport answers /0/public/Time with
+            """
+            {"error": [], "result": {"unixtime": 1700000000, "rfc1123": "Tue, 14 Nov 23 22:13:20 +0000"}}
+            """
+        Then the server time from the transport should be 1700000000
+        And the transport should have received 2 GET requests to /0/public/Time
+
+    Scenario: Retried private calls are signed again with a fresh nonce
+        Given an exchange instance with an in-memory transport signing with aWprbG1ub3A=
+        And a retry middleware with 3 attempts
+        And the transport answers /0/private/OpenOrders with
+            """
+            {"error": ["EService:Unavailable"]}
+            """
+        And the transport answers /0/private/OpenOrders with
+            """
+            {"error": [], "result": {"open": {}}}
+            """
+        When an open orders request is sent and a response is received with 0 errors
+        Then the transport should have received 2 POST requests to /0/private/OpenOrders with a valid API-Sign
+        And the POST requests to /0/private/OpenOrders should all have different nonces
+
+    Scenario: Retries give up after the configured number of attempts
+        Given an exchange instance with an in-memory transport signing with aWprbG1ub3A=
+        And a retry middleware with 2 attempts
+        And the transport answers /0/private/OpenOrders with HTTP status 503
+        Then requesting open orders should fail with HTTP status 503
+        And the transport should have received 2 POST requests to /0/private/OpenOrders
+
+    Scenario: Order placement is never retried blindly
+        Given an exchange instance with an in-memory transport signing with aWprbG1ub3A=
+        And a retry middleware with 3 attempts
+        And the transport answers /0/private/AddOrder with HTTP status 502
+        Then placing an order should fail with HTTP status 502
+        And the transport should have received 1 POST request to /0/private/AddOrder
+
+    Scenario: Middlewares can change outgoing requests before they are signed
+        Given an exchange instance with an in-memory transport signing with aWprbG1ub3A=
+        And a middleware adding userref=42 to every request
+        And the transport answers /0/private/OpenOrders with
+            """
+            {"error": [], "result": {"open": {}}}
+            """
+        When an open orders request is sent and a response is received with 0 errors
+        Then the transport should have received 1 POST request to /0/private/OpenOrders with a valid API-Sign
+        And the POST request to /0/private/OpenOrders should contain userref=42
+
+    Scenario: Latency metrics are recorded per endpoint
+        Given an exchange instance with an in-memory transport signing with aWprbG1ub3A=
+        And a metrics middleware
+        And the transport answers /0/private/OpenOrders with
+            """
+            {"error": [], "result": {"open": {}}}
+            """
+        When an open orders request is sent and a response is received with 0 errors
+        And an open orders request is sent and a response is received with 0 errors
+        Then the metrics should report 2 calls and 0 errors for /private/OpenOrders
+
+    Scenario: Logged parameters have secrets redacted
+        Given a logging middleware
+        Then the logged parameters for nonce=1,otp=123456,pair=XBTUSD should be nonce=1&otp=***&pair=XBTUSD
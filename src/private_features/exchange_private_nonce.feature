@@ -0,0 +1,17 @@
+Feature: Private API nonces
+    Scenario: In-process nonces are strictly increasing across threads
+        Given an in-process nonce provider with <resolution> resolution
+        When 8 threads each take 500 nonces
+        Then every nonce should be unique and increasing within each thread
+
+        Examples:
+            | resolution |
+            | Millis     |
+            | Micros     |
+            | Nanos      |
+
+    Scenario: Persistent nonces are strictly increasing across providers sharing one file
+        Given 3 file nonce providers sharing one file
+        When 6 threads each take 50 nonces
+        Then every nonce should be unique and increasing within each thread
+        And the nonce file should hold the largest nonce issued
@@ -0,0 +1,50 @@
+Feature: Orders are checked against the loaded asset catalog before they are sent
+    Background:
+        Given an exchange instance with an in-memory transport signing with aWprbG1ub3A=
+        And the transport answers /0/public/Assets with
+            """
+            {
+                "error": [],
+                "result": {
+                    "XXBT": {"aclass": "currency", "altname": "XBT", "decimals": 10, "display_decimals": 5},
+                    "ZUSD": {"aclass": "currency", "altname": "USD", "decimals": 4, "display_decimals": 2}
+                }
+            }
+            """
+        And the transport answers /0/public/AssetPairs with
+            """
+            {
+                "error": [],
+                "result": {
+                    "XXBTZUSD": {
+                        "altname": "XBTUSD", "wsname": "XBT/USD", "aclass_base": "currency", "base": "XXBT",
+                        "aclass_quote": "currency", "quote": "ZUSD", "lot": "unit", "pair_decimals": 1,
+                        "lot_decimals": 8, "lot_multiplier": 1, "leverage_buy": [], "leverage_sell": [],
+                        "fees": [[0, 0.26]], "fees_maker": [[0, 0.16]], "fee_volume_currency": "ZUSD",
+                        "margin_call": 80, "margin_stop": 40, "ordermin": "0.0001", "costmin": "0.5", "tick_size": "0.5"
+                    }
+                }
+            }
+            """
+        And the transport answers /0/private/AddOrder with
+            """
+            {"error": [], "result": {"descr": {"order": "buy 1.25000000 XBTUSD @ limit 37500.0"}, "txid": ["OUF4EM-FRGI2-MQMWZD"]}}
+            """
+
+    Scenario: An order off the tick size is refused without being sent
+        When the exchange's asset catalog is refreshed
+        And an order for XBT/USD at 37500.2 is placed
+        Then placing the order should have been refused with "Price 37500.2 is not a multiple of the 0.5 tick size for XBTUSD."
+        And the transport should have received 0 POST requests to /0/private/AddOrder
+
+    Scenario: An order within the pair's rules is sent
+        When the exchange's asset catalog is refreshed
+        And an order for XBT/USD at 37500.5 is placed
+        Then the order should have been placed as OUF4EM-FRGI2-MQMWZD
+        And the transport should have received 1 POST request to /0/private/AddOrder with a valid API-Sign
+
+    Scenario: Without a loaded catalog the order is sent as is
+        When an order for XBTUSD at 37500.2 is placed
+        Then the order should have been placed as OUF4EM-FRGI2-MQMWZD
+        And the transport should have received 1 POST request to /0/private/AddOrder
+        And the transport should have received 0 GET requests to /0/public/AssetPairs
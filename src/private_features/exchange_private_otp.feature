@@ -0,0 +1,41 @@
+Feature: Private API one-time passwords
+    Scenario: TOTP codes match the RFC 6238 SHA-1 test vectors
+        Given a TOTP provider with secret GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ and <digits> digits
+        Then the code at <time> should be <code>
+
+        Examples:
+            | digits | time        | code     |
+            | 8      | 59          | 94287082 |
+            | 8      | 1111111109  | 07081804 |
+            | 8      | 1111111111  | 14050471 |
+            | 8      | 1234567890  | 89005924 |
+            | 8      | 2000000000  | 69279037 |
+            | 8      | 20000000000 | 65353130 |
+            | 6      | 59          | 287082   |
+            | 6      | 1111111109  | 081804   |
+
+    Scenario: Authenticator-app secrets ignore case, spaces and padding
+        Given a TOTP provider with secret gezd gnbv gy3t qojq gezd gnbv gy3t qojq==== and 6 digits
+        Then the code at 1234567890 should be 005924
+
+    Scenario: TOTP parameters that cannot produce codes are rejected
+        Then a TOTP provider with <digits> digits and a <step> second step should be rejected
+
+        Examples:
+            | digits | step |
+            | 6      | 0    |
+            | 5      | 30   |
+            | 9      | 30   |
+            | 10     | 30   |
+
+    Scenario: A fresh OTP is attached to private requests when 2FA is required
+        Given an exchange instance requiring 2FA signing with ijklmnop and TOTP secret GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ
+        When a private /private/OpenOrders request is prepared with nonce 12345 and parameters trades=true
+        Then the sent body should contain a 6 digit otp
+        And the API-Sign header should verify against the sent body
+
+    Scenario: A static password is attached to private requests when 2FA is required
+        Given an exchange instance requiring 2FA signing with ijklmnop and static password hunter2
+        When a private /private/OpenOrders request is prepared with nonce 12345 and parameters trades=true
+        Then the sent body should be nonce=12345&trades=true&otp=hunter2
+        And the API-Sign header should verify against the sent body
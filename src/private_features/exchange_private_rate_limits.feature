@@ -0,0 +1,99 @@
+Feature: Private API rate limits
+    Scenario: The API call counter rejects calls over the tier maximum
+        Given a <tier> tier rate limiter that rejects calls
+        When <calls> <endpoint> calls are made
+        Then <accepted> calls should be accepted and the rest rejected
+        And the API counter headroom should be <headroom>
+
+        Examples:
+            | tier         | calls | endpoint               | accepted | headroom |
+            | Starter      | 20    | /private/OpenOrders    | 15       | 0        |
+            | Starter      | 10    | /private/Ledgers       | 7        | 1        |
+            | Intermediate | 12    | /private/TradesHistory | 10       | 0        |
+            | Pro          | 5     | /public/AssetPairs     | 5        | 20       |
+
+    Scenario: Order placement is limited per pair
+        Given a Starter tier rate limiter that rejects calls
+        When 65 /private/AddOrder calls are made for XBTUSD
+        Then 60 calls should be accepted and the rest rejected
+        And the API counter headroom should be 15
+        When 5 /private/AddOrder calls are made for ETHUSD
+        Then 5 calls should be accepted and the rest rejected
+
+    Scenario: Orders and cancels are charged to one counter per pair, whatever name is used
+        Given an exchange instance with an in-memory transport signing with aWprbG1ub3A=
+        And the exchange has a Starter tier rate limiter that rejects calls
+        And the transport answers /0/public/Assets with
+            """
+            {
+                "error": [],
+                "result": {
+                    "XXBT": {"aclass": "currency", "altname": "XBT", "decimals": 10, "display_decimals": 5},
+                    "ZUSD": {"aclass": "currency", "altname": "USD", "decimals": 4, "display_decimals": 2}
+                }
+            }
+            """
+        And the transport answers /0/public/AssetPairs with
+            """
+            {
+                "error": [],
+                "result": {
+                    "XXBTZUSD": {
+                        "altname": "XBTUSD", "wsname": "XBT/USD", "aclass_base": "currency", "base": "XXBT",
+                        "aclass_quote": "currency", "quote": "ZUSD", "lot": "unit", "pair_decimals": 1,
+                        "lot_decimals": 8, "lot_multiplier": 1, "leverage_buy": [], "leverage_sell": [],
+                        "fees": [[0, 0.26]], "fees_maker": [[0, 0.16]], "fee_volume_currency": "ZUSD",
+                        "margin_call": 80, "margin_stop": 40, "ordermin": "0.0001"
+                    }
+                }
+            }
+            """
+        And the transport answers /0/private/AddOrder with
+            """
+            {"error": [], "result": {"descr": {"order": "buy 1.25000000 XBTUSD @ limit 37500.0"}, "txid": ["OUF4EM-FRGI2-MQMWZD"]}}
+            """
+        And the transport answers /0/private/CancelOrder with
+            """
+            {"error": [], "result": {"count": 1}}
+            """
+        When an order for XBTUSD is placed
+        And an order for XBT/USD is placed
+        And an order for XXBTZUSD is placed
+        Then the order-rate headroom for BTC/USD should be 57
+        When order OUF4EM-FRGI2-MQMWZD is canceled
+        Then the order-rate headroom for XBTUSD should be 49
+
+    Scenario: Concurrent orders under different names share one counter
+        Given an exchange instance with an in-memory transport signing with aWprbG1ub3A=
+        And the exchange has a Starter tier rate limiter that rejects calls
+        And the transport answers /0/public/Assets with
+            """
+            {
+                "error": [],
+                "result": {
+                    "XXBT": {"aclass": "currency", "altname": "XBT", "decimals": 10, "display_decimals": 5},
+                    "ZUSD": {"aclass": "currency", "altname": "USD", "decimals": 4, "display_decimals": 2}
+                }
+            }
+            """
+        And the transport answers /0/public/AssetPairs with
+            """
+            {
+                "error": [],
+                "result": {
+                    "XXBTZUSD": {
+                        "altname": "XBTUSD", "wsname": "XBT/USD", "aclass_base": "currency", "base": "XXBT",
+                        "aclass_quote": "currency", "quote": "ZUSD", "lot": "unit", "pair_decimals": 1,
+                        "lot_decimals": 8, "lot_multiplier": 1, "leverage_buy": [], "leverage_sell": [],
+                        "fees": [[0, 0.26]], "fees_maker": [[0, 0.16]], "fee_volume_currency": "ZUSD",
+                        "margin_call": 80, "margin_stop": 40, "ordermin": "0.0001"
+                    }
+                }
+            }
+            """
+        And the transport answers /0/private/AddOrder with
+            """
+            {"error": [], "result": {"descr": {"order": "buy 1.25000000 XBTUSD @ limit 37500.0"}, "txid": ["OUF4EM-FRGI2-MQMWZD"]}}
+            """
+        When 6 orders alternating between XBTUSD and XXBTZUSD are placed at once
+        Then the order-rate headroom for XBT/USD should be 54
@@ -0,0 +1,122 @@
+Feature: Retry policy and safe order retries
+    Scenario: Idempotent calls are retried under the exchange's retry policy
+        Given an exchange instance with an in-memory transport signing with aWprbG1ub3A=
+        And a retry policy with 3 attempts
+        And the transport answers /0/private/OpenOrders with HTTP status 500
+        And the transport answers /0/private/OpenOrders with
+            """
+            {"error": [], "result": {"open": {}}}
+            """
+        When an open orders request is sent and a response is received with 0 errors
+        Then the transport should have received 2 POST requests to /0/private/OpenOrders with a valid API-Sign
+
+    Scenario: A retry middleware does not retry again under the exchange's retry policy
+        Given an exchange instance with an in-memory transport signing with aWprbG1ub3A=
+        And a retry middleware with 3 attempts
+        And a retry policy with 3 attempts
+        And the transport answers /0/private/OpenOrders with HTTP status 503
+        Then requesting open orders should fail with HTTP status 503
+        And the transport should have received 3 POST requests to /0/private/OpenOrders
+
+    Scenario: An order that landed despite a lost response is not sent again
+        Given an exchange instance with an in-memory transport signing with aWprbG1ub3A=
+        And a retry policy with 3 attempts
+        And the transport answers /0/private/AddOrder with HTTP status 504
+        And the transport answers /0/private/OpenOrders with
+            """
+            {"error": [], "result": {"open": {"OQCLML-BW3P3-BUCMWZ": {"cl_ord_id": "6d1b345e-2821-40e2-ad83-4ecb18a06876", "status": "open", "descr": {"order": "buy 1.25000000 XBTUSD @ limit 37500.0"}}}}}
+            """
+        When an order is placed with client order id 6d1b345e-2821-40e2-ad83-4ecb18a06876
+        Then the order should have been placed as OQCLML-BW3P3-BUCMWZ
+        And the transport should have received 1 POST request to /0/private/AddOrder with a valid API-Sign
+        And the transport should have received 1 POST request to /0/private/OpenOrders with a valid API-Sign
+        And the POST request to /0/private/OpenOrders should contain cl_ord_id=6d1b345e-2821-40e2-ad83-4ecb18a06876
+
+    Scenario: An order that did not land is sent again with the same client order id
+        Given an exchange instance with an in-memory transport signing with aWprbG1ub3A=
+        And a retry policy with 3 attempts
+        And the transport answers /0/private/AddOrder with HTTP status 502
+        And the transport answers /0/private/AddOrder with
+            """
+            {"error": [], "result": {"descr": {"order": "buy 1.25000000 XBTUSD @ limit 37500.0"}, "txid": ["OUF4EM-FRGI2-MQMWZD"]}}
+            """
+        And the transport answers /0/private/OpenOrders with
+            """
+            {"error": [], "result": {"open": {}}}
+            """
+        And the transport answers /0/private/ClosedOrders with
+            """
+            {"error": [], "result": {"closed": {}, "count": 0}}
+            """
+        When an order is placed
+        Then the order should have been placed as OUF4EM-FRGI2-MQMWZD
+        And the transport should have received 2 POST requests to /0/private/AddOrder with a valid API-Sign
+        And every POST request to /0/private/AddOrder should carry the same client order id
+        And the transport should have received 1 POST request to /0/private/ClosedOrders
+
+    Scenario: An order is not sent again when the lookup fails
+        Given an exchange instance with an in-memory transport signing with aWprbG1ub3A=
+        And a retry policy with 2 attempts
+        And the transport answers /0/private/AddOrder with HTTP status 502
+        And the transport answers /0/private/OpenOrders with HTTP status 503
+        When an order is placed
+        Then placing the order should have failed with HTTP status 502
+        And the transport should have received 1 POST request to /0/private/AddOrder
+
+    Scenario: A rejected order is sent again without a lookup
+        Given an exchange instance with an in-memory transport signing with aWprbG1ub3A=
+        And a retry policy with 3 attempts
+        And the transport answers /0/private/AddOrder with
+            """
+            {"error": ["EService:Unavailable"]}
+            """
+        And the transport answers /0/private/AddOrder with
+            """
+            {"error": [], "result": {"descr": {"order": "buy 1.25000000 XBTUSD @ limit 37500.0"}, "txid": ["OUF4EM-FRGI2-MQMWZD"]}}
+            """
+        When an order is placed
+        Then the order should have been placed as OUF4EM-FRGI2-MQMWZD
+        And the transport should have received 2 POST requests to /0/private/AddOrder
+        And the transport should have received 0 POST requests to /0/private/OpenOrders
+
+    Scenario: An order with a userref that landed despite a lost response is found by its userref
+        Given an exchange instance with an in-memory transport signing with aWprbG1ub3A=
+        And a retry policy with 3 attempts
+        And the transport answers /0/private/AddOrder with HTTP status 504
+        And the transport answers /0/private/OpenOrders with
+            """
+            {"error": [], "result": {"open": {
+                "OLDORD-BW3P3-BUCMWZ": {"userref": 7, "opentm": 1688666559.8974, "status": "open", "descr": {"order": "buy 2.00000000 XBTUSD @ limit 30000.0"}},
+                "OQCLML-BW3P3-BUCMWZ": {"userref": 7, "opentm": 4102444800.0, "status": "open", "descr": {"order": "buy 1.25000000 XBTUSD @ limit 37500.0"}}
+            }}}
+            """
+        When an order is placed with userref 7
+        Then the order should have been placed as OQCLML-BW3P3-BUCMWZ
+        And the transport should have received 1 POST request to /0/private/AddOrder with a valid API-Sign
+        And the POST request to /0/private/AddOrder should contain userref=7
+        And no POST request to /0/private/AddOrder should contain a cl_ord_id parameter
+        And the POST request to /0/private/OpenOrders should contain userref=7
+
+    Scenario: An order with a userref is sent again when only older orders share its userref
+        Given an exchange instance with an in-memory transport signing with aWprbG1ub3A=
+        And a retry policy with 3 attempts
+        And the transport answers /0/private/AddOrder with HTTP status 502
+        And the transport answers /0/private/AddOrder with
+            """
+            {"error": [], "result": {"descr": {"order": "buy 1.25000000 XBTUSD @ limit 37500.0"}, "txid": ["OUF4EM-FRGI2-MQMWZD"]}}
+            """
+        And the transport answers /0/private/OpenOrders with
+            """
+            {"error": [], "result": {"open": {
+                "OLDORD-BW3P3-BUCMWZ": {"userref": 7, "opentm": 1688666559.8974, "status": "open", "descr": {"order": "buy 2.00000000 XBTUSD @ limit 30000.0"}}
+            }}}
+            """
+        And the transport answers /0/private/ClosedOrders with
+            """
+            {"error": [], "result": {"closed": {}, "count": 0}}
+            """
+        When an order is placed with userref 7
+        Then the order should have been placed as OUF4EM-FRGI2-MQMWZD
+        And the transport should have received 2 POST requests to /0/private/AddOrder with a valid API-Sign
+        And no POST request to /0/private/AddOrder should contain a cl_ord_id parameter
+        And the transport should have received 1 POST request to /0/private/ClosedOrders
@@ -0,0 +1,21 @@
+Feature: Private API secret handling
+    Scenario: Debug output never contains credentials
+        Given an exchange instance requiring 2FA signing with kQH5HW/8p1uGOVjbgWA7FunAmGO8lsSUXNsu3eow76sz84Q18fWxnyRzBHCd3pd5nE9qa99HAZtuZuj6F1huXg== and static password hunter2
+        Then the debug output should not contain kQH5HW/8p1uGOVjbgWA7FunAmGO8lsSUXNsu3eow76sz84Q18fWxnyRzBHCd3pd5nE9qa99HAZtuZuj6F1huXg==
+        And the debug output should not contain hunter2
+        And the debug output should not contain the decoded secret bytes
+        And the debug output should contain ***
+
+    Scenario: Debug output of prepared and sign-tested requests never contains credentials
+        Given an exchange instance requiring 2FA signing with kQH5HW/8p1uGOVjbgWA7FunAmGO8lsSUXNsu3eow76sz84Q18fWxnyRzBHCd3pd5nE9qa99HAZtuZuj6F1huXg== and static password hunter2
+        When a private /private/OpenOrders request is prepared with nonce 12345 and parameters trades=true
+        And using kQH5HW/8p1uGOVjbgWA7FunAmGO8lsSUXNsu3eow76sz84Q18fWxnyRzBHCd3pd5nE9qa99HAZtuZuj6F1huXg==, 12345, /0/private/OpenOrders, and nonce=12345&trades=true&otp=hunter2 for sign testing
+        Then the debug output should not contain kQH5HW/8p1uGOVjbgWA7FunAmGO8lsSUXNsu3eow76sz84Q18fWxnyRzBHCd3pd5nE9qa99HAZtuZuj6F1huXg==
+        And the debug output should not contain hunter2
+        And the debug output should not contain the API-Sign header
+        And the debug output should contain Sensitive
+        And the debug output should not contain the decoded secret bytes
+        And the debug output should contain otp=***
+
+    Scenario: Secrets that are not valid base64 are rejected when loaded
+        Then loading the secret not-base64!! should fail
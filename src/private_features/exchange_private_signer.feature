@@ -0,0 +1,21 @@
+Feature: Out-of-process request signing
+    Scenario: A signing daemon produces the documented signature for an approved endpoint
+        Given an exchange instance signing through a daemon holding kQH5HW/8p1uGOVjbgWA7FunAmGO8lsSUXNsu3eow76sz84Q18fWxnyRzBHCd3pd5nE9qa99HAZtuZuj6F1huXg== for /private/AddOrder
+        When a private /private/AddOrder request is prepared with nonce 1616492376594 and parameters ordertype=limit,pair=XBTUSD,price=37500,type=buy,volume=1.25
+        Then the API-Sign header should be 4/dpxb3iT4tp/ZCVEwSnEsLxx0bqyhLpdfOpc6fn7OR8+UClSV5n9E6aSS8MPtnRfp32bAb0nmbRn6H8ndwLUQ==
+        And the API-Sign header should verify against the sent body
+        And the exchange should not hold an API secret
+
+    Scenario: A signing daemon refuses endpoints that are not approved
+        Given an exchange instance signing through a daemon holding aWprbG1ub3A= for /private/OpenOrders
+        When a private /private/OpenOrders request is prepared with nonce 12345 and parameters trades=true
+        Then the API-Sign header should verify against the sent body
+        And preparing a private /private/Withdraw request should fail with a signing error
+
+    Scenario: The daemon socket is only accessible to its owner
+        Given an exchange instance signing through a daemon holding aWprbG1ub3A= for /private/OpenOrders
+        Then the signer socket should only be accessible to its owner
+
+    Scenario: Concurrent calls are signed through the daemon
+        Given an exchange instance signing through a daemon holding aWprbG1ub3A= for /private/OpenOrders
+        Then 8 private /private/OpenOrders requests signed through the daemon at once should all verify
@@ -0,0 +1,47 @@
+Feature: Private request body encoding and signing
+    Scenario: The documented AddOrder example produces the documented signature
+        Given an exchange instance signing with kQH5HW/8p1uGOVjbgWA7FunAmGO8lsSUXNsu3eow76sz84Q18fWxnyRzBHCd3pd5nE9qa99HAZtuZuj6F1huXg==
+        When a private /private/AddOrder request is prepared with nonce 1616492376594 and parameters ordertype=limit,pair=XBTUSD,price=37500,type=buy,volume=1.25
+        Then the sent body should be nonce=1616492376594&ordertype=limit&pair=XBTUSD&price=37500&type=buy&volume=1.25
+        And the API-Sign header should be 4/dpxb3iT4tp/ZCVEwSnEsLxx0bqyhLpdfOpc6fn7OR8+UClSV5n9E6aSS8MPtnRfp32bAb0nmbRn6H8ndwLUQ==
+        And the API-Sign header should verify against the sent body
+
+    Scenario: Parameters keep their order and escaping between the signed payload and the sent body
+        Given an exchange instance signing with <secret>
+        When a private <endpoint> request is prepared with nonce <nonce> and parameters <params>
+        Then the sent body should be <body>
+        And the API-Sign header should verify against the sent body
+
+        Examples:
+            | secret   | endpoint             | nonce | params                                  | body                                                          |
+            | 11111111 | /private/OpenOrders  | 12345 | trades=true,userref=42                  | nonce=12345&trades=true&userref=42                            |
+            | ijklmnop | /private/QueryOrders | 67890 | txid[]=OABC12-DEF34-GHI56,txid[]=OXYZ98 | nonce=67890&txid%5B%5D=OABC12-DEF34-GHI56&txid%5B%5D=OXYZ98   |
+            | abcdefgh | /private/AddOrder    | 54321 | pair=XBT/USD,oflags=post+fcib,otp=a&b   | nonce=54321&pair=XBT%2FUSD&oflags=post%2Bfcib&otp=a%26b       |
+
+    Scenario: Signatures only verify for the exact secret, path, nonce and body
+        Given an exchange instance signing with kQH5HW/8p1uGOVjbgWA7FunAmGO8lsSUXNsu3eow76sz84Q18fWxnyRzBHCd3pd5nE9qa99HAZtuZuj6F1huXg==
+        When a private /private/AddOrder request is prepared with nonce 1616492376594 and parameters ordertype=limit,pair=XBTUSD,price=37500,type=buy,volume=1.25
+        Then the API-Sign header should verify against the sent body
+        And the API-Sign header should not verify with the secret aWprbG1ub3A=
+        And the API-Sign header should not verify for the path /0/private/CancelOrder
+        And the API-Sign header should not verify with the nonce 1616492376595
+        And the API-Sign header should not verify with the body nonce=1616492376594&ordertype=limit&pair=XBTUSD&price=37500&type=buy&volume=12.5
+        And the signature not-base64! should not verify
+
+    Scenario: Signatures match known vectors, including 2FA bodies with an otp
+        When using <private_key>, <nonce>, <endpoint>, and <body> for sign testing
+        Then the resulting value should be equal to <signed>
+
+        Examples:
+            | private_key                                                                              | nonce         | endpoint               | body                                                                                                                                                 | signed                                                                                   |
+            | 11111111                                                                                 | 12345         | /test-route-1          | 12345nonce=12345                                                                                                                                     | kiokL1J/JcwAqnbpekTnIlQgcmCrFcRcLkalCwo82Xk2eljKD1XxMrUXpiyX1zRXIoM5BGMD+VZCFJ30hkukGA== |
+            | ijklmnop                                                                                 | 67890         | /test-route-2          | 67890nonce=67890                                                                                                                                     | /43JHatvokXs2Vp9Per4FP8uBtNNGT6ICbxz112L6vkSHOvZ6YD9+XWJAYxfzKgmGcNsbiwT8jLKqmrNadrcKw== |
+            | abcdefgh                                                                                 | 54321         | /test-route-3          | 54321nonce=54321                                                                                                                                     | /D0mmIQeTY1o4Lr11ZVw6Yvg6SVNP9Iq39ky2mvguQXE3fxe1ZlJDnADDpEQq3xV6hSyuE5ekiH4PuYDViMLkw== |
+            | 11111111                                                                                 | 12345         | /test-route-1          | nonce=12345                                                                                                                                          | TGPI0fJN4KWxMtlM5Aljjnem8B0SBaZyMlsN9oHPg/5z7OJjvNu+5LWsM43YZRpImIgQefCTI30LRm5FzUsS9w== |
+            | ijklmnop                                                                                 | 67890         | /test-route-2          | nonce=67890                                                                                                                                          | Bigdb9FsQMNcsI4iDnrux18MQjITFl9EalvTWKDxtovjoxerFK1iThvAHwAQGLb4WzCLQXcOYv2IkWR6gFO8kw== |
+            | abcdefgh                                                                                 | 54321         | /test-route-3          | nonce=54321                                                                                                                                          | HdRlgH+X4SKDUINgZjHnae1V6Pqi/wlQSOOvgmzQumUk4smGwLgz8s+l/mcWnj79I8LhLJfUfjsKHZB9uiZ6AA== |
+            | kQH5HW/8p1uGOVjbgWA7FunAmGO8lsSUXNsu3eow76sz84Q18fWxnyRzBHCd3pd5nE9qa99HAZtuZuj6F1huXg== | 1616492376594 | /0/private/AddOrder    | nonce=1616492376594&ordertype=limit&pair=XBTUSD&price=37500&type=buy&volume=1.25                                                                     | 4/dpxb3iT4tp/ZCVEwSnEsLxx0bqyhLpdfOpc6fn7OR8+UClSV5n9E6aSS8MPtnRfp32bAb0nmbRn6H8ndwLUQ== |
+            | kQH5HW/8p1uGOVjbgWA7FunAmGO8lsSUXNsu3eow76sz84Q18fWxnyRzBHCd3pd5nE9qa99HAZtuZuj6F1huXg== | 1616492376595 | /0/private/AddOrder    | nonce=1616492376595&ordertype=stop-loss-limit&type=sell&volume=0.5&pair=XBT%2FUSD&price=%2B5%25&price2=41000&oflags=post%2Cfcib&userref=7&otp=123456 | PxlasiccUDl+ZCobZSMnXOLAWDAPenKjuXVpzUGh8/PENX/6HZWGC7+353eEA/CLcSjYWF5Pq3Zysd33hn7lZw== |
+            | ijklmnop                                                                                 | 1616492376600 | /0/private/QueryOrders | nonce=1616492376600&txid%5B%5D=OABC12-DEF34-GHI56&txid%5B%5D=OXYZ98-UVW76-RST54&trades=true                                                          | rKwyo5Y0L2U/2/wfKVgNIdMICkU7DmQoz8lxulCiaAU5gwdKcwpBP4hg3Op5B0bmIES7LiDBfUUFmQ5wPU/sdw== |
+            | abcdefgh                                                                                 | 1616492376700 | /0/private/CancelOrder | nonce=1616492376700&txid=OABC12-DEF34-GHI56&otp=a%26b%3Dc                                                                                            | qYkC9IYsEzwdZL087h8xuTQVwce8Ql5SMit/b/9PI+XBUn7Fc/+bsCfHAtYvsgXCi974T6S+3snO2KvyIXe7Nw== |
+            | 11111111                                                                                 | 1616492376800 | /0/private/OpenOrders  | nonce=1616492376800                                                                                                                                  | 4ibi36Oicw7vZh41V7ubu+JxRpSD5XM3gipY36u+AW30Logprt6Tp8OC/hhSMn+IEbvOWQxgBF9OBxnxS1TAdg== |
@@ -0,0 +1,9 @@
+Feature: Polling endpoints as streams
+    Scenario: Open orders are polled until the stream is dropped
+        Given an exchange instance with an in-memory transport signing with aWprbG1ub3A=
+        And the transport answers /0/private/OpenOrders with
+            """
+            {"error": [], "result": {"open": {}}}
+            """
+        When open orders are watched for 3 polls
+        Then the transport should have received 3 POST requests to /0/private/OpenOrders with a valid API-Sign
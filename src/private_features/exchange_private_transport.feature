@@ -0,0 +1,33 @@
+Feature: Pluggable HTTP transport
+    Scenario: Private requests are sent signed through an in-memory transport
+        Given an exchange instance with an in-memory transport signing with kQH5HW/8p1uGOVjbgWA7FunAmGO8lsSUXNsu3eow76sz84Q18fWxnyRzBHCd3pd5nE9qa99HAZtuZuj6F1huXg==
+        And the transport answers /0/private/OpenOrders with
+            """
+            {"error": [], "result": {"open": {}}}
+            """
+        When an open orders request is sent and a response is received with 0 errors
+        Then the response should contain the OpenOrders result
+        And the transport should have received 1 POST request to /0/private/OpenOrders with a valid API-Sign
+
+    Scenario: Public requests go through the same transport
+        Given an exchange instance with an in-memory transport signing with aWprbG1ub3A=
+        And the transport answers /0/public/Time with
+            """
+            {"error": [], "result": {"unixtime": 1700000000, "rfc1123": "Tue, 14 Nov 23 22:13:20 +0000"}}
+            """
+        Then the server time from the transport should be 1700000000
+        And the transport should have received 1 GET request to /0/public/Time
+
+    Scenario: Exchange errors returned through the transport are surfaced
+        Given an exchange instance with an in-memory transport signing with aWprbG1ub3A=
+        And the transport answers /0/private/OpenOrders with
+            """
+            {"error": ["EAPI:Invalid nonce"]}
+            """
+        When an open orders request is sent and a response is received with 1 errors
+        Then the transport should have received 1 POST request to /0/private/OpenOrders with a valid API-Sign
+
+    Scenario: HTTP error statuses returned through the transport are surfaced
+        Given an exchange instance with an in-memory transport signing with aWprbG1ub3A=
+        And the transport answers /0/private/OpenOrders with HTTP status 502
+        Then requesting open orders should fail with HTTP status 502
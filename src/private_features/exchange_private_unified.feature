@@ -0,0 +1,143 @@
+Feature: Venue-neutral Exchange trait
+    Background:
+        Given an exchange instance with an in-memory transport signing with aWprbG1ub3A=
+        And the transport answers /0/public/Assets with
+            """
+            {
+                "error": [],
+                "result": {
+                    "XXBT": {"aclass": "currency", "altname": "XBT", "decimals": 10, "display_decimals": 5},
+                    "XETH": {"aclass": "currency", "altname": "ETH", "decimals": 10, "display_decimals": 5},
+                    "ZUSD": {"aclass": "currency", "altname": "USD", "decimals": 4, "display_decimals": 2}
+                }
+            }
+            """
+        And the transport answers /0/public/AssetPairs with
+            """
+            {
+                "error": [],
+                "result": {
+                    "XXBTZUSD": {
+                        "altname": "XBTUSD", "wsname": "XBT/USD", "aclass_base": "currency", "base": "XXBT",
+                        "aclass_quote": "currency", "quote": "ZUSD", "lot": "unit", "pair_decimals": 1,
+                        "lot_decimals": 8, "lot_multiplier": 1, "leverage_buy": [], "leverage_sell": [],
+                        "fees": [[0, 0.26]], "fees_maker": [[0, 0.16]], "fee_volume_currency": "ZUSD",
+                        "margin_call": 80, "margin_stop": 40, "ordermin": "0.0001", "tick_size": "0.1"
+                    },
+                    "XETHZUSD": {
+                        "altname": "ETHUSD", "wsname": "ETH/USD", "aclass_base": "currency", "base": "XETH",
+                        "aclass_quote": "currency", "quote": "ZUSD", "lot": "unit", "pair_decimals": 2,
+                        "lot_decimals": 8, "lot_multiplier": 1, "leverage_buy": [], "leverage_sell": [],
+                        "fees": [[0, 0.26]], "fees_maker": [[0, 0.16]], "fee_volume_currency": "ZUSD",
+                        "margin_call": 80, "margin_stop": 40, "ordermin": "0.002"
+                    }
+                }
+            }
+            """
+
+    Scenario: Markets use common asset codes
+        Then the BTC/USD market should trade BTC against USD in steps of 0.1
+        And the ETH/USD market should trade ETH against USD in steps of 0.01
+        And the transport should have received 1 GET request to /0/public/AssetPairs
+        When the exchange's asset catalog is refreshed
+        Then the BTC/USD market should trade BTC against USD in steps of 0.1
+        And the transport should have received 2 GET requests to /0/public/Assets
+        And the transport should have received 2 GET requests to /0/public/AssetPairs
+
+    Scenario: Tickers are requested by symbol
+        Given the transport answers /0/public/Ticker with
+            """
+            {
+                "error": [],
+                "result": {
+                    "XXBTZUSD": {
+                        "a": ["52609.60000", "1", "1.000"], "b": ["52609.50000", "1", "1.000"],
+                        "c": ["52641.10000", "0.00080000"], "v": ["1920.83610601", "7954.00219674"],
+                        "p": ["52389.94668", "54022.90683"], "t": [23329, 80463],
+                        "l": ["51513.90000", "51513.90000"], "h": ["53219.90000", "57200.00000"], "o": "52280.40000"
+                    }
+                }
+            }
+            """
+        Then the btc/usd ticker should have bid 52609.50000, ask 52609.60000 and last 52641.10000
+        And the last GET request to /0/public/Ticker should have the query pair=XBTUSD
+
+    Scenario: Balances are keyed by common asset codes and skip empty assets
+        Given the transport answers /0/private/Balance with
+            """
+            {"error": [], "result": {"XXBT": "0.5000000000", "ZUSD": "1000.2500", "XETH": "0.0000000000", "XBT.F": "0.1000000000"}}
+            """
+        Then the unified balances should be BTC=0.5000000000,USD=1000.2500,XBT.F=0.1000000000
+        And the transport should have received 1 POST request to /0/private/Balance with a valid API-Sign
+
+    Scenario: Orders are placed by symbol
+        Given the transport answers /0/private/AddOrder with
+            """
+            {"error": [], "result": {"descr": {"order": "buy 0.50000000 XBTUSD @ limit 30000.0"}, "txid": ["OUF4EM-FRGI2-MQMWZD"]}}
+            """
+        When a unified buy order for 0.5 BTC/USD at 30000.0 is placed
+        Then the unified order id should be OUF4EM-FRGI2-MQMWZD
+        And the POST request to /0/private/AddOrder should contain pair=XBTUSD
+        And the POST request to /0/private/AddOrder should contain ordertype=limit
+        And the POST request to /0/private/AddOrder should contain oflags=post
+        When a unified sell order for 0.5 BTC/USD is placed
+        Then the transport should have received 2 POST requests to /0/private/AddOrder with a valid API-Sign
+        And the transport should have received 1 GET request to /0/public/Assets
+        And the transport should have received 1 GET request to /0/public/AssetPairs
+
+    Scenario: Open orders are reported by symbol
+        Given the transport answers /0/private/OpenOrders with
+            """
+            {"error": [], "result": {"open": {
+                "OQCLML-BW3P3-BUCMWZ": {"status": "open", "opentm": 1688666559.8974, "descr": {"pair": "XBTUSD", "type": "buy", "ordertype": "limit", "price": "30000.0", "price2": "0", "leverage": "none", "order": "buy 1.25000000 XBTUSD @ limit 30000.0"}, "vol": "1.25000000", "vol_exec": "0.00000000", "cost": "0.00000", "fee": "0.00000", "price": "0.00000", "misc": "", "oflags": "fciq"},
+                "OB5VMB-B4U2U-DK2WRW": {"status": "pending", "opentm": 1688666560.1, "descr": {"pair": "ETHUSD", "type": "sell", "ordertype": "market", "price": "0", "price2": "0", "leverage": "none", "order": "sell 2.00000000 ETHUSD @ market"}, "vol": "2.00000000", "vol_exec": "0.00000000", "cost": "0.00000", "fee": "0.00000", "price": "0.00000", "misc": "", "oflags": "fciq"},
+                "OLTCMB-B4U2U-DK2WRW": {"status": "open", "opentm": 1688666561.1, "descr": {"pair": "LTCUSD", "type": "buy", "ordertype": "limit", "price": "70.00", "price2": "0", "leverage": "none", "order": "buy 3.00000000 LTCUSD @ limit 70.00"}, "vol": "3.00000000", "vol_exec": "0.00000000", "cost": "0.00000", "fee": "0.00000", "price": "0.00000", "misc": "", "oflags": "fciq"}
+            }}}
+            """
+        Then unified open order OQCLML-BW3P3-BUCMWZ should be a buy of 1.25000000 BTC/USD at 30000.0
+        And unified open order OB5VMB-B4U2U-DK2WRW should be a sell of 2.00000000 ETH/USD at market
+        And unified open order OLTCMB-B4U2U-DK2WRW should be a buy of 3.00000000 LTCUSD at 70.00
+
+    Scenario: Cancels succeed only when an order was canceled
+        Given the transport answers /0/private/CancelOrder with
+            """
+            {"error": [], "result": {"count": 1}}
+            """
+        And the transport answers /0/private/CancelOrder with
+            """
+            {"error": [], "result": {"count": 0}}
+            """
+        Then canceling unified order OQCLML-BW3P3-BUCMWZ should succeed
+        And canceling unified order OQCLML-BW3P3-BUCMWZ should fail as an unknown order
+        And the POST request to /0/private/CancelOrder should contain txid=OQCLML-BW3P3-BUCMWZ
+
+    Scenario: Fills are reported most recent first
+        Given the transport answers /0/private/TradesHistory with
+            """
+            {"error": [], "result": {"count": 2, "trades": {
+                "THVRQM-33VKH-UCI7BS": {"ordertxid": "OB5VMB-B4U2U-DK2WRW", "postxid": "TKH2SE-M7IF5-CFI7LT", "pair": "XETHZUSD", "time": 1688667100.1, "type": "sell", "ordertype": "market", "price": "1900.00", "cost": "3800.00", "fee": "9.88", "vol": "2.00000000", "margin": "0.00000", "misc": ""},
+                "TCWJEG-FL4SZ-3FKGH6": {"ordertxid": "OQCLML-BW3P3-BUCMWZ", "postxid": "TKH2SE-M7IF5-CFI7LT", "pair": "XXBTZUSD", "time": 1688667796.8802, "type": "buy", "ordertype": "limit", "price": "30000.0", "cost": "600.00", "fee": "0.96", "vol": "0.02000000", "margin": "0.00000", "misc": "", "maker": true}
+            }}}
+            """
+        Then the unified fills should be TCWJEG-FL4SZ-3FKGH6:OQCLML-BW3P3-BUCMWZ:BTC/USD:0.02000000@30000.0,THVRQM-33VKH-UCI7BS:OB5VMB-B4U2U-DK2WRW:ETH/USD:2.00000000@1900.00
+
+    Scenario: Fills are read from every page of the trades history
+        Given the transport answers /0/private/TradesHistory with
+            """
+            {"error": [], "result": {"count": 3, "trades": {
+                "TCWJEG-FL4SZ-3FKGH6": {"ordertxid": "OQCLML-BW3P3-BUCMWZ", "postxid": "TKH2SE-M7IF5-CFI7LT", "pair": "XXBTZUSD", "time": 1688667796.8802, "type": "buy", "ordertype": "limit", "price": "30000.0", "cost": "600.00", "fee": "0.96", "vol": "0.02000000", "margin": "0.00000", "misc": ""},
+                "THVRQM-33VKH-UCI7BS": {"ordertxid": "OB5VMB-B4U2U-DK2WRW", "postxid": "TKH2SE-M7IF5-CFI7LT", "pair": "XETHZUSD", "time": 1688667100.1, "type": "sell", "ordertype": "market", "price": "1900.00", "cost": "3800.00", "fee": "9.88", "vol": "2.00000000", "margin": "0.00000", "misc": ""}
+            }}}
+            """
+        And the transport answers /0/private/TradesHistory with
+            """
+            {"error": [], "result": {"count": 3, "trades": {
+                "TLTCEG-FL4SZ-3FKGH6": {"ordertxid": "OLTCMB-B4U2U-DK2WRW", "postxid": "TKH2SE-M7IF5-CFI7LT", "pair": "XLTCZUSD", "time": 1688666000.5, "type": "buy", "ordertype": "limit", "price": "70.00", "cost": "210.00", "fee": "0.34", "vol": "3.00000000", "margin": "0.00000", "misc": ""}
+            }}}
+            """
+        Then the unified fills should be TCWJEG-FL4SZ-3FKGH6:OQCLML-BW3P3-BUCMWZ:BTC/USD:0.02000000@30000.0,THVRQM-33VKH-UCI7BS:OB5VMB-B4U2U-DK2WRW:ETH/USD:2.00000000@1900.00,TLTCEG-FL4SZ-3FKGH6:OLTCMB-B4U2U-DK2WRW:XLTCZUSD:3.00000000@70.00
+        And the transport should have received 2 POST requests to /0/private/TradesHistory with a valid API-Sign
+        And the last POST request to /0/private/TradesHistory should contain ofs=2
+
+    Scenario: The venue name is taken from the base URL unless configured
+        Then the unified venue should be api.example.com
@@ -0,0 +1,28 @@
+Feature: Encrypted credential vault
+    Scenario: Profiles added to the vault are loaded by the exchange
+        Given a new vault with passphrase correct-horse
+        And the vault profile main with API key main-key and secret kQH5HW/8p1uGOVjbgWA7FunAmGO8lsSUXNsu3eow76sz84Q18fWxnyRzBHCd3pd5nE9qa99HAZtuZuj6F1huXg==
+        And the vault profile readonly with API key readonly-key and secret aWprbG1ub3A=
+        When the vault is saved
+        Then the vault opened with passphrase correct-horse should list main,readonly
+        And the vault file should not contain the secret kQH5HW/8p1uGOVjbgWA7FunAmGO8lsSUXNsu3eow76sz84Q18fWxnyRzBHCd3pd5nE9qa99HAZtuZuj6F1huXg==
+        When the exchange loads the main profile from the vault with passphrase correct-horse
+        Then the exchange should use API key main-key without 2FA
+
+    Scenario: Rotated and removed profiles are persisted
+        Given a new vault with passphrase correct-horse
+        And the vault profile main with API key main-key and secret aWprbG1ub3A=
+        And the vault profile old with API key old-key and secret aWprbG1ub3A=
+        When the main profile is rotated to API key new-key and secret bmV3LXNlY3JldA==
+        And the old profile is removed from the vault
+        And the vault is saved
+        Then the vault opened with passphrase correct-horse should list main
+        When the exchange loads the main profile from the vault with passphrase correct-horse
+        Then the exchange should use API key new-key without 2FA
+
+    Scenario: The vault cannot be opened with the wrong passphrase
+        Given a new vault with passphrase correct-horse
+        And the vault profile main with API key main-key and secret aWprbG1ub3A=
+        When the vault is saved
+        Then opening the vault with passphrase battery-staple should fail
+        And loading the missing profile from the vault with passphrase correct-horse should fail
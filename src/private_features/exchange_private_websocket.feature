@@ -0,0 +1,67 @@
+Feature: WebSocket API tokens and requests
+    Background:
+        Given an exchange instance with an in-memory transport signing with aWprbG1ub3A=
+        And the transport answers /0/public/Assets with
+            """
+            {
+                "error": [],
+                "result": {
+                    "XXBT": {"aclass": "currency", "altname": "XBT", "decimals": 10, "display_decimals": 5},
+                    "XETH": {"aclass": "currency", "altname": "ETH", "decimals": 10, "display_decimals": 5},
+                    "ZUSD": {"aclass": "currency", "altname": "USD", "decimals": 4, "display_decimals": 2}
+                }
+            }
+            """
+        And the transport answers /0/public/AssetPairs with
+            """
+            {
+                "error": [],
+                "result": {
+                    "XXBTZUSD": {
+                        "altname": "XBTUSD", "wsname": "XBT/USD", "aclass_base": "currency", "base": "XXBT",
+                        "aclass_quote": "currency", "quote": "ZUSD", "lot": "unit", "pair_decimals": 1,
+                        "lot_decimals": 8, "lot_multiplier": 1, "leverage_buy": [], "leverage_sell": [],
+                        "fees": [[0, 0.26]], "fees_maker": [[0, 0.16]], "fee_volume_currency": "ZUSD",
+                        "margin_call": 80, "margin_stop": 40, "ordermin": "0.0001"
+                    },
+                    "XETHZUSD": {
+                        "altname": "ETHUSD", "wsname": "ETH/USD", "aclass_base": "currency", "base": "XETH",
+                        "aclass_quote": "currency", "quote": "ZUSD", "lot": "unit", "pair_decimals": 2,
+                        "lot_decimals": 8, "lot_multiplier": 1, "leverage_buy": [], "leverage_sell": [],
+                        "fees": [[0, 0.26]], "fees_maker": [[0, 0.16]], "fee_volume_currency": "ZUSD",
+                        "margin_call": 80, "margin_stop": 40, "ordermin": "0.002"
+                    }
+                }
+            }
+            """
+        And the transport answers /0/private/GetWebSocketsToken with
+            """
+            {"error": [], "result": {"token": "1Dwc4lzSwNWOAwkMdqhssNNFhs1ed606d1WcF3XfEMw", "expires": 900}}
+            """
+        When the asset catalog is loaded
+
+    Scenario: Tokens are requested through a signed private call
+        When a WebSockets token is requested
+        Then the WebSockets token should expire in 900 seconds
+        And the transport should have received 1 POST request to /0/private/GetWebSocketsToken with a valid API-Sign
+
+    Scenario: Public subscriptions name pairs by their v2 symbol
+        Then the subscribe request for ticker on XBTUSD,eth/usd should be
+            """
+            {"method": "subscribe", "params": {"channel": "ticker", "symbol": ["BTC/USD", "ETH/USD"]}}
+            """
+        And the unsubscribe request for book on XXBTZUSD should be
+            """
+            {"method": "unsubscribe", "params": {"channel": "book", "symbol": ["BTC/USD"]}}
+            """
+
+    Scenario: Private subscriptions carry the token
+        When a WebSockets token is requested
+        Then the subscribe request for executions on XBTUSD with the token should be
+            """
+            {"method": "subscribe", "params": {"channel": "executions", "symbol": ["BTC/USD"], "token": "1Dwc4lzSwNWOAwkMdqhssNNFhs1ed606d1WcF3XfEMw"}, "req_id": 7}
+            """
+
+    Scenario: WebSocket endpoints are set on the builder
+        Given an exchange built with WebSocket URLs wss://ws.example.com/v2 and wss://ws-auth.example.com/v2
+        Then the exchange's WebSocket URLs should be wss://ws.example.com/v2 and wss://ws-auth.example.com/v2
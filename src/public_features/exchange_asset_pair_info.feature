@@ -0,0 +1,88 @@
+Feature: Asset pair information
+    Background:
+        Given an exchange instance with an in-memory transport signing with aWprbG1ub3A=
+
+    Scenario: Full pair information includes status and position limits
+        Given the transport answers /0/public/AssetPairs with
+            """
+            {
+                "error": [],
+                "result": {
+                    "XXBTZUSD": {
+                        "altname": "XBTUSD", "wsname": "XBT/USD", "aclass_base": "currency", "base": "XXBT",
+                        "aclass_quote": "currency", "quote": "ZUSD", "lot": "unit", "cost_decimals": 5, "pair_decimals": 1,
+                        "lot_decimals": 8, "lot_multiplier": 1, "leverage_buy": [2, 3, 4, 5], "leverage_sell": [2, 3, 4, 5],
+                        "fees": [[0, 0.26], [50000, 0.24]], "fees_maker": [[0, 0.16], [50000, 0.14]], "fee_volume_currency": "ZUSD",
+                        "margin_call": 80, "margin_stop": 40, "ordermin": "0.0001", "costmin": "0.5", "tick_size": "0.1",
+                        "status": "online", "long_position_limit": 270, "short_position_limit": 180
+                    }
+                }
+            }
+            """
+        When the info asset pair XBTUSD is requested
+        Then the XXBTZUSD pair should be online with a long position limit of 270
+        And the last GET request to /0/public/AssetPairs should have the query pair=XBTUSD
+
+    Scenario: Leverage information
+        Given the transport answers /0/public/AssetPairs with
+            """
+            {
+                "error": [],
+                "result": {
+                    "XXBTZUSD": {"leverage_buy": [2, 3, 4, 5], "leverage_sell": [2, 3]}
+                }
+            }
+            """
+        When the leverage asset pair XBTUSD is requested
+        Then the XXBTZUSD leverage should be 2,3,4,5 to buy and 2,3 to sell
+        And the last GET request to /0/public/AssetPairs should have the query pair=XBTUSD&info=leverage
+
+    Scenario: Fee information
+        Given the transport answers /0/public/AssetPairs with
+            """
+            {
+                "error": [],
+                "result": {
+                    "XXBTZUSD": {
+                        "fees": [[0, 0.26], [50000, 0.24]], "fees_maker": [[0, 0.16], [50000, 0.14]],
+                        "fee_volume_currency": "ZUSD"
+                    }
+                }
+            }
+            """
+        When the fees asset pair XBTUSD is requested
+        Then the XXBTZUSD fees should be 0.24 taker and 0.14 maker at a volume of 60000
+        And the last GET request to /0/public/AssetPairs should have the query pair=XBTUSD&info=fees
+
+    Scenario: Margin information
+        Given the transport answers /0/public/AssetPairs with
+            """
+            {
+                "error": [],
+                "result": {
+                    "XXBTZUSD": {"margin_call": 80, "margin_stop": 40}
+                }
+            }
+            """
+        When the margin asset pair XBTUSD is requested
+        Then the XXBTZUSD margin call should be at 80% and liquidation at 40%
+        And the last GET request to /0/public/AssetPairs should have the query pair=XBTUSD&info=margin
+
+    Scenario: Pairs in a status this version does not know still parse
+        Given the transport answers /0/public/AssetPairs with
+            """
+            {
+                "error": [],
+                "result": {
+                    "XDGUSD": {
+                        "altname": "XDGUSD", "wsname": "XDG/USD", "aclass_base": "currency", "base": "XXDG",
+                        "aclass_quote": "currency", "quote": "ZUSD", "lot": "unit", "pair_decimals": 7,
+                        "lot_decimals": 8, "lot_multiplier": 1, "leverage_buy": [], "leverage_sell": [],
+                        "fees": [[0, 0.26]], "fees_maker": [[0, 0.16]], "fee_volume_currency": "ZUSD",
+                        "margin_call": 80, "margin_stop": 40, "ordermin": "50", "status": "delisted"
+                    }
+                }
+            }
+            """
+        When the info asset pair XDGUSD is requested
+        Then the XDGUSD pair should be unknown
@@ -0,0 +1,100 @@
+Feature: Asset and pair names
+    Background:
+        Given an exchange instance with an in-memory transport signing with aWprbG1ub3A=
+        And the transport answers /0/public/Assets with
+            """
+            {
+                "error": [],
+                "result": {
+                    "XXBT": {"aclass": "currency", "altname": "XBT", "decimals": 10, "display_decimals": 5, "collateral_value": 1.0, "status": "enabled"},
+                    "XETH": {"aclass": "currency", "altname": "ETH", "decimals": 10, "display_decimals": 5, "collateral_value": 1.0, "status": "enabled"},
+                    "XXDG": {"aclass": "currency", "altname": "XDG", "decimals": 8, "display_decimals": 2, "status": "enabled"},
+                    "ZUSD": {"aclass": "currency", "altname": "USD", "decimals": 4, "display_decimals": 2, "status": "enabled"}
+                }
+            }
+            """
+        And the transport answers /0/public/AssetPairs with
+            """
+            {
+                "error": [],
+                "result": {
+                    "XXBTZUSD": {
+                        "altname": "XBTUSD", "wsname": "XBT/USD", "aclass_base": "currency", "base": "XXBT",
+                        "aclass_quote": "currency", "quote": "ZUSD", "lot": "unit", "pair_decimals": 1,
+                        "lot_decimals": 8, "lot_multiplier": 1, "leverage_buy": [2, 3, 4, 5], "leverage_sell": [2, 3, 4, 5],
+                        "fees": [[0, 0.26]], "fees_maker": [[0, 0.16]], "fee_volume_currency": "ZUSD",
+                        "margin_call": 80, "margin_stop": 40, "ordermin": "0.0001"
+                    },
+                    "XETHXXBT": {
+                        "altname": "ETHXBT", "wsname": "ETH/XBT", "aclass_base": "currency", "base": "XETH",
+                        "aclass_quote": "currency", "quote": "XXBT", "lot": "unit", "pair_decimals": 5,
+                        "lot_decimals": 8, "lot_multiplier": 1, "leverage_buy": [2, 3, 4, 5], "leverage_sell": [2, 3, 4, 5],
+                        "fees": [[0, 0.26]], "fees_maker": [[0, 0.16]], "fee_volume_currency": "ZUSD",
+                        "margin_call": 80, "margin_stop": 40, "ordermin": "0.002"
+                    },
+                    "XDGUSD": {
+                        "altname": "XDGUSD", "wsname": "XDG/USD", "aclass_base": "currency", "base": "XXDG",
+                        "aclass_quote": "currency", "quote": "ZUSD", "lot": "unit", "pair_decimals": 7,
+                        "lot_decimals": 8, "lot_multiplier": 1, "leverage_buy": [2, 3], "leverage_sell": [2, 3],
+                        "fees": [[0, 0.26]], "fees_maker": [[0, 0.16]], "fee_volume_currency": "ZUSD",
+                        "margin_call": 80, "margin_stop": 40, "ordermin": "50"
+                    }
+                }
+            }
+            """
+        When the asset catalog is loaded
+
+    Scenario: Asset aliases resolve to the canonical id
+        Then the asset <name> should resolve to <id>
+
+        Examples:
+            | name | id   |
+            | XXBT | XXBT |
+            | XBT  | XXBT |
+            | BTC  | XXBT |
+            | btc  | XXBT |
+            | XDG  | XXDG |
+            | DOGE | XXDG |
+            | ZUSD | ZUSD |
+            | USD  | ZUSD |
+
+    Scenario: Pair aliases resolve to the canonical id
+        Then the pair <name> should resolve to <id>
+
+        Examples:
+            | name     | id       |
+            | XXBTZUSD | XXBTZUSD |
+            | XBTUSD   | XXBTZUSD |
+            | XBT/USD  | XXBTZUSD |
+            | BTC/USD  | XXBTZUSD |
+            | btc-usd  | XXBTZUSD |
+            | ETH/BTC  | XETHXXBT |
+            | DOGE/USD | XDGUSD   |
+            | XDG/USD  | XDGUSD   |
+
+    Scenario: Pairs are found from their base and quote assets
+        Then the pair trading BTC against USD should be XXBTZUSD
+        And the pair trading XETH against XBT should be XETHXXBT
+        And the pair trading doge against ZUSD should be XDGUSD
+
+    Scenario: Unknown names are an error
+        Then the asset FOO should not resolve
+        And the pair FOO/USD should not resolve
+        And the pair USD/BTC should not resolve
+
+    Scenario: Pairs are written in each API's form
+        Then the pair <name> should be written <written> for <format>
+
+        Examples:
+            | name     | written  | format    |
+            | BTC/USD  | XXBTZUSD | Id        |
+            | BTC/USD  | XBTUSD   | Rest      |
+            | BTC/USD  | XBT/USD  | WebSocket |
+            | XBTUSD   | BTC/USD  | Symbol    |
+            | XDGUSD   | DOGE/USD | Symbol    |
+            | XETHXXBT | ETH/XBT  | WebSocket |
+            | XETHXXBT | ETH/BTC  | Symbol    |
+
+    Scenario: Trading pair requests send the pair name resolved through the catalog
+        Given a BTC/USD trading pair request is sent
+        Then the last GET request to /0/public/AssetPairs should have the query pair=XBTUSD
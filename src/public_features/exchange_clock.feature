@@ -0,0 +1,28 @@
+Feature: Exchange clock offset
+    Scenario: The clock offset is measured from the middle of the round trip
+        Given a clock sync with a skew threshold of 2 seconds
+        When the server reports <server_time> for a request sent at <sent>ms and answered at <received>ms
+        Then the clock offset should be <offset>ms with a round trip of <round_trip>ms
+        And the skew warning should <warning> raised
+
+        Examples:
+            | server_time | sent          | received      | offset | round_trip | warning |
+            | 1700000000  | 1699999999800 | 1700000000200 | 500    | 400        | not be  |
+            | 1700000005  | 1700000000000 | 1700000000100 | 5450   | 100        | be      |
+            | 1699999999  | 1700000000000 | 1700000000000 | -500   | 0          | not be  |
+            | 1699999990  | 1700000000000 | 1700000000050 | -9525  | 50         | be      |
+            | 1700000001  | 1700000000400 | 1700000000600 | 1000   | 200        | not be  |
+
+    Scenario: Absolute order times are converted to the server clock
+        Given a clock sync with a skew threshold of 2 seconds
+        When the server reports 1700000005 for a request sent at 1700000000000ms and answered at 1700000000000ms
+        And an order expiring at local time 1700000100 seconds is prepared
+        Then the order expiretm parameter should be 1700000105
+        When an order starting at local time 1700000060 seconds is prepared
+        Then the order starttm parameter should be 1700000065
+
+    Scenario: Relative order times are left to the server clock
+        Given a clock sync with a skew threshold of 2 seconds
+        When the server reports 1700000005 for a request sent at 1700000000000ms and answered at 1700000000000ms
+        And an order expiring after 30 seconds is prepared
+        Then the order expiretm parameter should be +30
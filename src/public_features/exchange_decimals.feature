@@ -0,0 +1,69 @@
+Feature: Exact decimal amounts
+    Scenario: Order amounts are exact whether the exchange sends strings or numbers
+        Given the open orders response
+            """
+            {
+                "error": [],
+                "result": {
+                    "open": {
+                        "OQCLML-BW3P3-BUCMWZ": {
+                            "refid": null, "userref": 0, "status": "open",
+                            "opentm": 1688666559.8974, "starttm": 0, "expiretm": 0,
+                            "descr": {
+                                "pair": "XBTUSD", "type": "buy", "ordertype": "limit",
+                                "price": "30010.0", "price2": "0", "leverage": "none",
+                                "order": "buy 1.25000000 XBTUSD @ limit 30010.0", "close": ""
+                            },
+                            "vol": "1.25000000", "vol_exec": 0.375,
+                            "cost": "0.1", "fee": 0.2, "price": "30010.0",
+                            "stopprice": "0.00000", "limitprice": "0.00000",
+                            "misc": "", "oflags": "fciq"
+                        }
+                    }
+                }
+            }
+            """
+        Then order OQCLML-BW3P3-BUCMWZ should have 0.875 left to execute
+        And order OQCLML-BW3P3-BUCMWZ should have cost 0.3 including fees
+
+    Scenario: Fee tiers and order minimums are exact
+        Given the asset pairs response
+            """
+            {
+                "error": [],
+                "result": {
+                    "XXBTZUSD": {
+                        "altname": "XBTUSD", "wsname": "XBT/USD",
+                        "aclass_base": "currency", "base": "XXBT",
+                        "aclass_quote": "currency", "quote": "ZUSD",
+                        "lot": "unit", "pair_decimals": 1, "lot_decimals": 8, "lot_multiplier": 1,
+                        "leverage_buy": [2, 3, 4, 5], "leverage_sell": [2, 3, 4, 5],
+                        "fees": [[0, 0.26], [50000, 0.24], [100000, "0.22"]],
+                        "fees_maker": [[0, 0.16], [50000, 0.14], [100000, "0.12"]],
+                        "fee_volume_currency": "ZUSD",
+                        "margin_call": 80, "margin_stop": 40,
+                        "ordermin": "0.0001"
+                    }
+                }
+            }
+            """
+        Then the XXBTZUSD taker fee at a volume of 0 should be 0.26
+        And the XXBTZUSD taker fee at a volume of 75000.50 should be 0.24
+        And the XXBTZUSD maker fee at a volume of 100000 should be 0.12
+        And the XXBTZUSD minimum order should be 0.0001
+
+    Scenario: Order prices are sent exactly as given
+        When a sell stop-loss-limit order for 0.50000000 at <price> with secondary price <price2> is prepared
+        Then the order volume parameter should be 0.50000000
+        And the order price parameter should be <price>
+        And the order price2 parameter should be <price2>
+
+        Examples:
+            | price    | price2   |
+            | 41000.10 | 40999.9  |
+            | +5%      | 41000    |
+            | -150.5   | #0.25%   |
+
+    Scenario: Percent prices need an offset sign
+        Then the order price 5% should be rejected
+        And the order price abc should be rejected
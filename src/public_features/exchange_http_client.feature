@@ -0,0 +1,44 @@
+Feature: HTTP client configuration
+    Scenario: Requests carry the configured user agent through the configured proxy
+        Given a local HTTP server
+        And an exchange built with
+            | base_url        | http://api.example.com |
+            | proxy           | local server           |
+            | user_agent      | trading-bot/1.0        |
+            | connect_timeout | 2                      |
+            | request_timeout | 5                      |
+            | tcp_keepalive   | 30                     |
+        Then requesting the server time should return 1700000000
+        And the local server should have received "GET http://api.example.com/0/public/Time HTTP/1.1" from "trading-bot/1.0"
+
+    Scenario: Exchanges sharing one client reuse its connections
+        Given a local HTTP server
+        And 3 exchanges for the local server sharing one client
+        When each exchange requests the server time
+        Then the local server should have accepted 1 connection for 3 requests
+
+    Scenario: Exchanges with their own clients open their own connections
+        Given a local HTTP server
+        And 3 exchanges for the local server with their own clients
+        When each exchange requests the server time
+        Then the local server should have accepted 3 connections for 3 requests
+
+    Scenario: Idle connections are not kept when the pool size is zero
+        Given a local HTTP server
+        And an exchange built with
+            | base_url               | local server |
+            | pool_max_idle_per_host | 0            |
+        Then requesting the server time should return 1700000000
+        And requesting the server time should return 1700000000
+        And the local server should have accepted 2 connections for 2 requests
+
+    Scenario: Requests give up after the request timeout
+        Given a local HTTP server that never answers
+        And an exchange built with
+            | base_url        | local server |
+            | request_timeout | 1            |
+        Then requesting the server time should time out within 5 seconds
+
+    Scenario: An invalid proxy is a configuration error
+        Then building an exchange with these settings should fail
+            | proxy | not a proxy url |
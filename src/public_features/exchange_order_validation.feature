@@ -0,0 +1,63 @@
+Feature: Order validation against pair rules
+    Background:
+        Given the asset pairs response
+            """
+            {
+                "error": [],
+                "result": {
+                    "XXBTZUSD": {
+                        "altname": "XBTUSD", "wsname": "XBT/USD", "aclass_base": "currency", "base": "XXBT",
+                        "aclass_quote": "currency", "quote": "ZUSD", "lot": "unit", "pair_decimals": 1,
+                        "lot_decimals": 8, "lot_multiplier": 1, "leverage_buy": [2, 3, 4, 5], "leverage_sell": [2, 3],
+                        "fees": [[0, 0.26]], "fees_maker": [[0, 0.16]], "fee_volume_currency": "ZUSD",
+                        "margin_call": 80, "margin_stop": 40, "ordermin": "0.0001", "costmin": "0.5", "tick_size": "0.5"
+                    },
+                    "XDGUSD": {
+                        "altname": "XDGUSD", "wsname": "XDG/USD", "aclass_base": "currency", "base": "XXDG",
+                        "aclass_quote": "currency", "quote": "ZUSD", "lot": "unit", "pair_decimals": 7,
+                        "lot_decimals": 8, "lot_multiplier": 1, "leverage_buy": [], "leverage_sell": [],
+                        "fees": [[0, 0.26]], "fees_maker": [[0, 0.16]], "fee_volume_currency": "ZUSD",
+                        "margin_call": 80, "margin_stop": 40, "ordermin": "50"
+                    }
+                }
+            }
+            """
+
+    Scenario: Orders within the rules are sent unchanged
+        When a buy limit order for 0.25 at 37500.5 with leverage 5:1 is checked against XXBTZUSD
+        Then the order should be accepted
+        And the order price parameter should be 37500.5
+        And the order volume parameter should be 0.25
+        And the order leverage parameter should be 5:1
+
+    Scenario: Prices are rounded to the tick size and volumes down to the lot decimals
+        When a <side> limit order for <volume> at <price> is fitted to <pair>
+        Then the order should be accepted
+        And the order price parameter should be <sent_price>
+        And the order volume parameter should be <sent_volume>
+
+        Examples:
+            | side | pair     | volume       | price       | sent_price  | sent_volume |
+            | buy  | XXBTZUSD | 0.123456789  | 37500.3     | 37500.5     | 0.12345678  |
+            | sell | XXBTZUSD | 1            | 37500.2     | 37500.0     | 1           |
+            | buy  | XXBTZUSD | 0.5          | +12.7       | +12.5       | 0.5         |
+            | buy  | XXBTZUSD | 0.5          | +1.33%      | +1.33%      | 0.5         |
+            | buy  | XDGUSD   | 100          | 0.123456789 | 0.1234568   | 100         |
+
+    Scenario: Orders are refused with a description of the broken rule
+        When a <side> limit order for <volume> at <price> with leverage <leverage> is checked against <pair>
+        Then the order should be refused with "<message>"
+
+        Examples:
+            | side | pair     | volume      | price       | leverage | message                                                                        |
+            | buy  | XXBTZUSD | 0.5         | 37500.3     | none     | Price 37500.3 is not a multiple of the 0.5 tick size for XBTUSD.               |
+            | buy  | XXBTZUSD | 0.123456789 | 37500       | none     | Volume 0.123456789 has more than the 8 decimals allowed for XBTUSD.            |
+            | buy  | XXBTZUSD | 0.00005     | 37500       | none     | Volume 0.00005 is below the minimum of 0.0001 for XBTUSD.                      |
+            | buy  | XXBTZUSD | 0.0001      | 1000        | none     | Order value 0.1000 is below the minimum of 0.5 for XBTUSD.                     |
+            | sell | XXBTZUSD | 0.5         | 37500       | 5:1      | Leverage 5:1 is not offered to sell orders on XBTUSD (offered: 2, 3).          |
+            | buy  | XDGUSD   | 100         | 0.1         | 2:1      | XDGUSD does not offer leverage to buy orders.                                  |
+            | buy  | XDGUSD   | 10          | 0.1         | none     | Volume 10 is below the minimum of 50 for XDGUSD.                               |
+
+    Scenario: Orders below the minimum are refused even when rounding
+        When a buy limit order for 0.00001 at 37500 is fitted to XXBTZUSD
+        Then the order should be refused with "Volume 0.00001 is below the minimum of 0.0001 for XBTUSD."
@@ -0,0 +1,66 @@
+Feature: Order models
+    Background:
+        Given the open orders response
+            """
+            {
+                "error": [],
+                "result": {
+                    "open": {
+                        "OQCLML-BW3P3-BUCMWZ": {
+                            "refid": null, "userref": 0, "status": "open",
+                            "opentm": 1688666559.8974, "starttm": 0, "expiretm": 0,
+                            "descr": {
+                                "pair": "XBTUSD", "type": "buy", "ordertype": "limit",
+                                "price": "30010.0", "price2": "0", "leverage": "none",
+                                "order": "buy 1.25000000 XBTUSD @ limit 30010.0", "close": ""
+                            },
+                            "vol": "1.25000000", "vol_exec": "0.37500000",
+                            "cost": "11253.7", "fee": "0.00000", "price": "30010.0",
+                            "stopprice": "0.00000", "limitprice": "0.00000",
+                            "misc": "", "oflags": "fciq",
+                            "trades": ["TCCCTY-WE2O6-P3NB37"]
+                        },
+                        "OB5VMB-B4U2U-DK2WRW": {
+                            "refid": "OQCLML-BW3P3-BUCMWZ", "userref": 45326, "cl_ord_id": "6d1b345e-2821-40e2-ad83-4ecb18a06876",
+                            "status": "pending",
+                            "opentm": 1688665899.5699, "starttm": 1688670000.25, "expiretm": "1688680000",
+                            "descr": {
+                                "pair": "XBTUSD", "type": "sell", "ordertype": "stop-loss-limit",
+                                "price": "27500.0", "price2": "27400.0", "leverage": "5:1",
+                                "order": "sell 0.27500000 XBTUSD @ stop loss 27500.0 -> limit 27400.0 with 5:1 leverage", "close": ""
+                            },
+                            "vol": "0.27500000", "vol_exec": "0.00000000",
+                            "cost": "0.00000", "fee": "0.00000", "price": "0.00000",
+                            "trigger": "index", "margin": true,
+                            "misc": "stopped,touched,newflag", "oflags": "post,fcib"
+                        }
+                    }
+                }
+            }
+            """
+
+    Scenario: Sides, order types and statuses are typed
+        Then order OQCLML-BW3P3-BUCMWZ should be an open buy limit order
+        And order OB5VMB-B4U2U-DK2WRW should be a pending sell stop-loss-limit order
+
+    Scenario: Times keep their fractional seconds and 0 means not set
+        Then order OQCLML-BW3P3-BUCMWZ should have been opened at 1688666559.8974
+        And order OQCLML-BW3P3-BUCMWZ should start at never
+        And order OQCLML-BW3P3-BUCMWZ should expire at never
+        And order OB5VMB-B4U2U-DK2WRW should start at 1688670000.25
+        And order OB5VMB-B4U2U-DK2WRW should expire at 1688680000
+
+    Scenario: Flags are read as sets and unknown flags are skipped
+        Then order OQCLML-BW3P3-BUCMWZ should have the order flags fciq and misc flags none
+        And order OB5VMB-B4U2U-DK2WRW should have the order flags post,fcib and misc flags stopped,touched
+
+    Scenario: Fields the exchange can omit are optional
+        Then order OQCLML-BW3P3-BUCMWZ should have a stop price
+        And order OB5VMB-B4U2U-DK2WRW should not have a stop price
+        And order OB5VMB-B4U2U-DK2WRW should have 0.275 left to execute
+
+    Scenario: Order flags are sent as a comma separated list
+        When a buy limit order for 1 at 30000 with flags fciq,post is prepared
+        Then the order oflags parameter should be post,fciq
+        And the order type parameter should be buy
+        And the order ordertype parameter should be limit
@@ -7,7 +7,7 @@ Feature: Exchange Public API Endpoints
     Scenario: Using a valid exchange instance, retrieve the server time and validate the response
         Given a server time request is sent
         When a server time response is received
-        Then the response time in minutes should equal the server time in minutes
+        Then the local clock should be within 60 seconds of the server time
         
     Scenario: Using a valid exchange instance, retrieve the XBT/USD trading pair and validate the response
         Given a XBT/USD trading pair request is sent
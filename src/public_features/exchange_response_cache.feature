@@ -0,0 +1,82 @@
+Feature: Response cache
+    Background:
+        Given an exchange instance with an in-memory transport signing with aWprbG1ub3A=
+        And the transport answers /0/public/AssetPairs with
+            """
+            {
+                "error": [],
+                "result": {
+                    "XXBTZUSD": {
+                        "altname": "XBTUSD", "wsname": "XBT/USD", "aclass_base": "currency", "base": "XXBT",
+                        "aclass_quote": "currency", "quote": "ZUSD", "lot": "unit", "pair_decimals": 1,
+                        "lot_decimals": 8, "lot_multiplier": 1, "leverage_buy": [2, 3, 4, 5], "leverage_sell": [2, 3, 4, 5],
+                        "fees": [[0, 0.26]], "fees_maker": [[0, 0.16]], "fee_volume_currency": "ZUSD",
+                        "margin_call": 80, "margin_stop": 40, "ordermin": "0.0001"
+                    }
+                }
+            }
+            """
+        And the transport answers /0/public/Time with
+            """
+            {"error": [], "result": {"unixtime": 1688666559, "rfc1123": "Thu,  6 Jul 23 18:02:39 +0000"}}
+            """
+
+    Scenario: Without a cache every call is sent
+        When the asset pairs are requested 3 times
+        Then the transport should have received 3 GET requests to /0/public/AssetPairs
+
+    Scenario: Cached endpoints are sent once within their TTL
+        Given the exchange caches /public/AssetPairs for 60 seconds
+        When the asset pairs are requested 3 times
+        Then the transport should have received 1 GET request to /0/public/AssetPairs
+        And the asset pairs should include XXBTZUSD
+
+    Scenario: Endpoints without a TTL are not cached
+        Given the exchange caches /public/AssetPairs for 60 seconds
+        Then the server time from the transport should be 1688666559
+        And the server time from the transport should be 1688666559
+        And the transport should have received 2 GET requests to /0/public/Time
+
+    Scenario: Expired responses are requested again
+        Given the exchange caches /public/AssetPairs for 0 seconds
+        When the asset pairs are requested 2 times
+        Then the transport should have received 2 GET requests to /0/public/AssetPairs
+
+    Scenario: Concurrent calls for the same data share one request
+        Given the exchange caches /public/AssetPairs for 60 seconds
+        When 8 tasks request the asset pairs at once
+        Then the transport should have received 1 GET request to /0/public/AssetPairs
+        And the response cache should have 0 calls in flight
+
+    Scenario: Exchanges with different base URLs do not share responses
+        Given the exchange caches /public/AssetPairs for 60 seconds
+        When the asset pairs are requested 1 time
+        And the exchange's base URL is changed to https://sandbox.example.com/0
+        And the asset pairs are requested 2 times
+        Then the transport should have received 1 GET request to /0/public/AssetPairs
+        And the transport should have received 1 GET request to /0/public/AssetPairs on https://sandbox.example.com
+        And the response cache should have 0 calls in flight
+
+    Scenario: Invalidated responses are requested again
+        Given the exchange caches /public/AssetPairs for 60 seconds
+        When the asset pairs are requested 1 time
+        And the cached /public/AssetPairs responses are invalidated
+        And the asset pairs are requested 1 time
+        Then the transport should have received 2 GET requests to /0/public/AssetPairs
+
+    Scenario: A cold start is warmed from a snapshot
+        Given the exchange caches /public/AssetPairs for 60 seconds
+        When the asset pairs are requested 1 time
+        And the cache is saved to a snapshot
+        And a new exchange caching /public/AssetPairs for 60 seconds is warmed from the snapshot
+        And the asset pairs are requested 1 time
+        Then 1 cached response should have been loaded
+        And the transport should have received 0 GET requests to /0/public/AssetPairs
+        And the asset pairs should include XXBTZUSD
+
+    Scenario: Expired snapshot entries are not loaded
+        Given the exchange caches /public/AssetPairs for 60 seconds
+        When the asset pairs are requested 1 time
+        And the cache is saved to a snapshot
+        And a new exchange caching /public/AssetPairs for 0 seconds is warmed from the snapshot
+        Then 0 cached responses should have been loaded